@@ -0,0 +1,395 @@
+//! Contains writing of manifest bundles for static sites.
+//!
+//! A [`ManifestBundle`] contains everything a static site needs to serve the manifest:
+//! the manifest file, icons generated from the source images in all configured sizes,
+//! and an HTML snippet file that should be included in the `<head>` of every page. It can be
+//! built in memory with [`ManifestBundle::build`] and written to the output directory
+//! with [`ManifestBundle::write`], so static site generators only need to call it once
+//! per build.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+
+use image::imageops::FilterType;
+use image::ImageFormat;
+use smart_default::SmartDefault;
+
+use crate::errors::ManifestError;
+use crate::integration::escape_xml;
+use crate::resources::IconResource;
+use crate::types::*;
+use crate::WebAppManifest;
+
+/// The icon size that is preferred for the `apple-touch-icon` link.
+const APPLE_TOUCH_ICON_SIZE: u32 = 180;
+
+/// Options that control which files are generated and where they are written.
+///
+/// Paths are relative to the output directory. A leading `/` is ignored, but absolute
+/// paths and paths with `..` components are rejected, so files are never written
+/// outside the output directory.
+#[derive(SmartDefault, Debug, Eq, PartialEq, Clone)]
+pub struct BundleOptions {
+    /// The path of the manifest file, relative to the output directory.
+    ///
+    /// Defaults to `manifest.webmanifest`.
+    #[default("manifest.webmanifest".to_string())]
+    pub manifest_path: String,
+
+    /// The directory of the generated icons, relative to the output directory.
+    ///
+    /// Defaults to `icons`.
+    #[default("icons".to_string())]
+    pub icons_dir: String,
+
+    /// The path of the HTML snippet file, relative to the output directory.
+    ///
+    /// Defaults to `head.html`.
+    #[default("head.html".to_string())]
+    pub head_path: String,
+
+    /// The URL path under which the output directory is served, used for URLs in the
+    /// manifest and the HTML snippet.
+    ///
+    /// Defaults to `/`.
+    #[default("/".to_string())]
+    pub base_path: String,
+
+    /// The sizes of the generated icons in pixels. Icons are always square.
+    ///
+    /// Defaults to `192` and `512`, which are the sizes required by most browsers.
+    #[default(vec![192, 512])]
+    pub icon_sizes: Vec<u32>,
+}
+
+/// A source image from which icons are generated.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SourceIcon {
+    /// The encoded image in any format supported by the `image` crate.
+    pub data: Vec<u8>,
+
+    /// The purpose of the generated icons. Each source icon should have a different purpose.
+    pub purpose: ImagePurpose,
+}
+
+/// A file of the bundle.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct BundleFile {
+    /// The path of the file, relative to the output directory.
+    pub path: PathBuf,
+
+    /// The contents of the file.
+    pub contents: Vec<u8>,
+}
+
+/// The manifest with its icons and the HTML snippet.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ManifestBundle {
+    /// The manifest with the generated icons added to its icons.
+    pub manifest: WebAppManifest,
+
+    /// The manifest file, the generated icon files and the HTML snippet file.
+    pub files: Vec<BundleFile>,
+
+    /// The HTML snippet that links the manifest and icons.
+    pub head: String,
+}
+
+impl ManifestBundle {
+    /// Builds the bundle in memory.
+    ///
+    /// Icons are resized and cropped to squares of each configured size and encoded as
+    /// PNG images. They are added to the manifest icons with URLs under the base path.
+    ///
+    /// # Parameters
+    ///
+    /// - `manifest`: The manifest to bundle. It should not be processed, so its URLs
+    ///   remain relative to the manifest file.
+    /// - `icons`: The source images of the icons.
+    /// - `options`: The options of the bundle.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::InvalidBundlePath`] if any of the paths is not within the output directory.
+    /// - [`ManifestError::Image`] if any of the source images cannot be decoded or encoded.
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn build(
+        manifest: &WebAppManifest,
+        icons: &[SourceIcon],
+        options: &BundleOptions,
+    ) -> Result<Self, ManifestError> {
+        let manifest_path = normalize_path(&options.manifest_path)?;
+        let icons_dir = normalize_path(&options.icons_dir)?;
+        let head_path = normalize_path(&options.head_path)?;
+
+        let mut manifest = manifest.clone();
+        let mut files = Vec::new();
+
+        let base_path = if options.base_path.ends_with('/') {
+            options.base_path.clone()
+        } else {
+            format!("{}/", options.base_path)
+        };
+
+        let mut generated = Vec::new();
+        for source in icons {
+            let image = image::load_from_memory(&source.data).map_err(image_error)?;
+
+            for &size in &options.icon_sizes {
+                let name = match source.purpose {
+                    ImagePurpose::Any => format!("icon-{}.png", size),
+                    purpose => format!("icon-{}-{}.png", purpose, size),
+                };
+
+                let mut contents = Vec::new();
+                image
+                    .resize_to_fill(size, size, FilterType::Lanczos3)
+                    .write_to(&mut Cursor::new(&mut contents), ImageFormat::Png)
+                    .map_err(image_error)?;
+
+                let path = Path::new(icons_dir).join(&name);
+                let src = format!("{}{}", base_path, join_url_path(icons_dir, &name));
+                files.push(BundleFile { path, contents });
+
+                generated.push(IconResource {
+                    src: Url::Relative(src),
+                    r#type: Some("image/png".parse().unwrap()),
+                    sizes: [ImageSize::Fixed(size, size)].iter().cloned().collect(),
+                    purpose: [source.purpose].iter().cloned().collect(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        let head = head_snippet(&manifest, &generated, &format!("{}{}", base_path, manifest_path));
+        manifest.icons.extend(generated);
+
+        let contents = serde_json::to_vec_pretty(&manifest)?;
+        files.insert(0, BundleFile { path: PathBuf::from(manifest_path), contents });
+        files.push(BundleFile {
+            path: PathBuf::from(head_path),
+            contents: head.clone().into_bytes(),
+        });
+
+        Ok(Self { manifest, files, head })
+    }
+
+    /// Writes the bundle files into the output directory.
+    ///
+    /// Missing directories are created and existing files are overwritten.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Io`] if any of the files cannot be written.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn write(&self, output_dir: &Path) -> Result<(), ManifestError> {
+        for file in &self.files {
+            let path = output_dir.join(&file.path);
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(io_error)?;
+            }
+
+            fs::write(&path, &file.contents).map_err(io_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates the HTML snippet that links the manifest and icons.
+fn head_snippet(manifest: &WebAppManifest, icons: &[IconResource], manifest_href: &str) -> String {
+    let mut head = format!("<link rel=\"manifest\" href=\"{}\">\n", escape_xml(manifest_href));
+
+    if let Some(theme_color) = &manifest.theme_color {
+        head.push_str(&format!(
+            "<meta name=\"theme-color\" content=\"{}\">\n",
+            theme_color.to_hex_string()
+        ));
+    }
+
+    let any_icons: Vec<_> = icons
+        .iter()
+        .filter_map(|icon| match (&icon.src, icon.sizes.iter().next()) {
+            (Url::Relative(src), Some(ImageSize::Fixed(size, _)))
+                if icon.purpose.contains(&ImagePurpose::Any) =>
+            {
+                Some((src, *size))
+            }
+            _ => None,
+        })
+        .collect();
+
+    for (src, size) in &any_icons {
+        head.push_str(&format!(
+            "<link rel=\"icon\" type=\"image/png\" sizes=\"{0}x{0}\" href=\"{1}\">\n",
+            size,
+            escape_xml(src)
+        ));
+    }
+
+    // Prefer the smallest icon that is not smaller than the recommended size
+    let apple_icon = any_icons
+        .iter()
+        .filter(|(_, size)| *size >= APPLE_TOUCH_ICON_SIZE)
+        .min_by_key(|(_, size)| *size)
+        .or_else(|| any_icons.iter().max_by_key(|(_, size)| *size));
+
+    if let Some((src, _)) = apple_icon {
+        head.push_str(&format!("<link rel=\"apple-touch-icon\" href=\"{}\">\n", escape_xml(src)));
+    }
+
+    head
+}
+
+/// Removes the leading slashes from the path and checks that it is within the output directory.
+#[allow(clippy::result_large_err)]
+fn normalize_path(path: &str) -> Result<&str, ManifestError> {
+    let path = path.trim_start_matches('/');
+
+    let is_parent = path.split(['/', '\\']).any(|segment| segment == "..");
+    let is_relative = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+
+    if is_parent || !is_relative {
+        return Err(ManifestError::InvalidBundlePath { path: path.to_string() });
+    }
+
+    Ok(path)
+}
+
+/// Joins the directory and file name into a URL path.
+fn join_url_path(dir: &str, name: &str) -> String {
+    let dir = dir.trim_matches('/');
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+fn image_error(error: image::ImageError) -> ManifestError {
+    ManifestError::Image { message: error.to_string() }
+}
+
+fn io_error(error: std::io::Error) -> ManifestError {
+    ManifestError::Io { message: error.to_string() }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+    use image::{Rgba, RgbaImage};
+
+    use super::*;
+
+    fn source(width: u32, height: u32, purpose: ImagePurpose) -> SourceIcon {
+        let mut data = Vec::new();
+        let image = RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 255]));
+        image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png).unwrap();
+        SourceIcon { data, purpose }
+    }
+
+    #[test]
+    fn test_build_bundle() {
+        let manifest = WebAppManifest {
+            name: Some("Example".to_string()),
+            theme_color: Some(csscolorparser::parse("red").unwrap()),
+            ..Default::default()
+        };
+
+        let icons = [source(64, 32, ImagePurpose::Any), source(64, 64, ImagePurpose::Maskable)];
+        let options = BundleOptions {
+            base_path: "/app".to_string(),
+            icon_sizes: vec![16, 192],
+            ..Default::default()
+        };
+        let bundle = ManifestBundle::build(&manifest, &icons, &options).unwrap();
+
+        let paths: Vec<_> = bundle
+            .files
+            .iter()
+            .map(|file| file.path.to_str().unwrap().replace('\\', "/"))
+            .collect();
+        assert_eq!(paths, [
+            "manifest.webmanifest",
+            "icons/icon-16.png",
+            "icons/icon-192.png",
+            "icons/icon-maskable-16.png",
+            "icons/icon-maskable-192.png",
+            "head.html",
+        ]);
+
+        let icon = image::load_from_memory(&bundle.files[2].contents).unwrap();
+        assert_eq!((icon.width(), icon.height()), (192, 192));
+
+        let written: WebAppManifest = serde_json::from_slice(&bundle.files[0].contents).unwrap();
+        assert_eq!(written, bundle.manifest);
+        assert_eq!(written.icons.len(), 4);
+        assert_eq!(written.icons[3].src, Url::Relative("/app/icons/icon-maskable-192.png".to_string()));
+
+        assert_eq!(bundle.head, concat!(
+            "<link rel=\"manifest\" href=\"/app/manifest.webmanifest\">\n",
+            "<meta name=\"theme-color\" content=\"#ff0000\">\n",
+            "<link rel=\"icon\" type=\"image/png\" sizes=\"16x16\" href=\"/app/icons/icon-16.png\">\n",
+            "<link rel=\"icon\" type=\"image/png\" sizes=\"192x192\" href=\"/app/icons/icon-192.png\">\n",
+            "<link rel=\"apple-touch-icon\" href=\"/app/icons/icon-192.png\">\n",
+        ));
+        assert_eq!(bundle.files[5].contents, bundle.head.as_bytes());
+    }
+
+    #[test]
+    fn test_bundle_paths() {
+        let options =
+            BundleOptions { manifest_path: "/manifest.json".to_string(), ..Default::default() };
+        let bundle = ManifestBundle::build(&WebAppManifest::default(), &[], &options).unwrap();
+        assert_eq!(bundle.files[0].path, PathBuf::from("manifest.json"));
+        assert!(bundle.head.starts_with("<link rel=\"manifest\" href=\"/manifest.json\">"));
+
+        for path in &["../manifest.json", "static/../../manifest.json", "..\\manifest.json"] {
+            let options = BundleOptions { manifest_path: path.to_string(), ..Default::default() };
+            let result = ManifestBundle::build(&WebAppManifest::default(), &[], &options);
+            assert_matches!(result, Err(ManifestError::InvalidBundlePath { .. }));
+        }
+
+        let options = BundleOptions { icons_dir: "../icons".to_string(), ..Default::default() };
+        let result = ManifestBundle::build(&WebAppManifest::default(), &[], &options);
+        assert_matches!(result, Err(ManifestError::InvalidBundlePath { .. }));
+
+        let options = BundleOptions { head_path: "..".to_string(), ..Default::default() };
+        let result = ManifestBundle::build(&WebAppManifest::default(), &[], &options);
+        assert_matches!(result, Err(ManifestError::InvalidBundlePath { .. }));
+    }
+
+    #[test]
+    fn test_invalid_source() {
+        let icons = [SourceIcon { data: b"invalid".to_vec(), purpose: ImagePurpose::Any }];
+        let result =
+            ManifestBundle::build(&WebAppManifest::default(), &icons, &BundleOptions::default());
+        assert_matches!(result, Err(ManifestError::Image { .. }));
+    }
+
+    #[test]
+    fn test_write_bundle() {
+        let output_dir =
+            std::env::temp_dir().join(format!("web-app-manifest-bundle-{}", std::process::id()));
+        let options = BundleOptions { icons_dir: "static/icons".to_string(), ..Default::default() };
+
+        let icons = [source(8, 8, ImagePurpose::Any)];
+        let bundle = ManifestBundle::build(&WebAppManifest::default(), &icons, &options).unwrap();
+        bundle.write(&output_dir).unwrap();
+
+        assert!(output_dir.join("manifest.webmanifest").is_file());
+        assert!(output_dir.join("static/icons/icon-512.png").is_file());
+        assert_eq!(fs::read_to_string(output_dir.join("head.html")).unwrap(), bundle.head);
+
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
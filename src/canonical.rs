@@ -0,0 +1,312 @@
+//! Contains the canonical serialization of manifests.
+//!
+//! The canonical serialization follows the [JSON Canonicalization Scheme][rfc8785] (JCS),
+//! so signatures and content hashes are interoperable with other tools that use it.
+//!
+//! Members and resource fields that equal their default values are omitted, so the
+//! defaults that the crate fills in when parsing do not end up in the canonical form.
+//! The output therefore only depends on the values set in the manifest document, and
+//! does not change when new members with default values are supported.
+//!
+//! [rfc8785]: https://www.rfc-editor.org/rfc/rfc8785
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::errors::ManifestError;
+use crate::resources::*;
+use crate::WebAppManifest;
+
+impl WebAppManifest {
+    /// Serializes the manifest to canonical JSON as defined by [RFC 8785][rfc8785].
+    ///
+    /// Object members are sorted by the UTF-16 code units of their keys, numbers are
+    /// formatted like in ECMAScript and no insignificant whitespace is emitted, so equal
+    /// manifests always produce the same output. Members and resource fields that equal
+    /// their default values are omitted, so a member that is explicitly set to its
+    /// default value produces the same output as a missing member.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The canonical JSON serialization.
+    /// - `Err`: An error in case the manifest cannot be serialized.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    /// [rfc8785]: https://www.rfc-editor.org/rfc/rfc8785
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn to_canonical_json(&self) -> Result<String, ManifestError> {
+        let defaults = resource_defaults()?;
+
+        let mut value = serde_json::to_value(self)?;
+        strip_defaults(&mut value, &serde_json::to_value(WebAppManifest::default())?, &defaults);
+
+        let mut output = String::new();
+        write_value(&value, &mut output)?;
+        Ok(output)
+    }
+
+    /// Computes a fingerprint of the manifest.
+    ///
+    /// The fingerprint is a 64-bit FNV-1a hash of the canonical JSON serialization,
+    /// formatted as a hexadecimal string. It is stable across crate versions and
+    /// platforms, so it can be used as a cache key or an HTTP entity tag, but it is
+    /// not collision-resistant and must not be used for integrity checks.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn fingerprint(&self) -> Result<String, ManifestError> {
+        Ok(fingerprint_bytes(self.to_canonical_json()?.as_bytes()))
+    }
+}
+
+/// Computes the FNV-1a fingerprint of bytes.
+pub(crate) fn fingerprint_bytes(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Serializes the manifest to canonical JSON bytes.
+#[cfg(feature = "signing")]
+#[allow(clippy::result_large_err)]
+pub(crate) fn to_canonical_vec(manifest: &WebAppManifest) -> Result<Vec<u8>, ManifestError> {
+    Ok(manifest.to_canonical_json()?.into_bytes())
+}
+
+/// Returns the serialized default values of the resources, keyed by the names of the
+/// fields that contain them.
+#[allow(clippy::result_large_err)]
+fn resource_defaults() -> Result<Vec<(&'static str, Value)>, ManifestError> {
+    fn default<T: Default + Serialize>(
+        name: &'static str,
+    ) -> Result<(&'static str, Value), ManifestError> {
+        Ok((name, serde_json::to_value(T::default())?))
+    }
+
+    Ok(vec![
+        default::<ScopeExtensionResource>("scope_extensions")?,
+        default::<TabStripResource>("tab_strip")?,
+        default::<HomeTabParams>("home_tab")?,
+        default::<NewTabButtonParams>("new_tab_button")?,
+        default::<UserPreferencesResource>("user_preferences")?,
+        default::<ExternalApplicationResource>("related_applications")?,
+        default::<ExternalApplicationFingerprint>("fingerprints")?,
+        default::<ProtocolHandlerResource>("protocol_handlers")?,
+        default::<FileHandlerResource>("file_handlers")?,
+        default::<LaunchHandlerResource>("launch_handler")?,
+        default::<ShortcutResource>("shortcuts")?,
+        default::<ShareTargetResource>("share_target")?,
+        default::<ShareTargetParams>("params")?,
+        default::<ShareTargetFiles>("files")?,
+        default::<NoteTakingResource>("note_taking")?,
+        default::<IconResource>("icons")?,
+        default::<ScreenshotResource>("screenshots")?,
+        default::<WidgetResource>("widgets")?,
+        default::<ServiceWorkerResource>("serviceworker")?,
+        default::<UrlHandlerResource>("url_handlers")?,
+    ])
+}
+
+/// Removes the fields of the object that equal their default values, and recursively
+/// does the same for the resources in its fields. Arrays apply the default to each item.
+fn strip_defaults(value: &mut Value, default: &Value, defaults: &[(&str, Value)]) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|key, item| default.get(key) != Some(item));
+
+            for (key, item) in object.iter_mut() {
+                if let Some((_, default)) = defaults.iter().find(|(name, _)| name == key) {
+                    strip_defaults(item, default, defaults);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                strip_defaults(item, default, defaults);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn write_value(value: &Value, output: &mut String) -> Result<(), ManifestError> {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => {
+            output.push_str(&serde_json::to_string(value)?);
+        }
+        Value::Number(number) => {
+            // All numbers are treated as IEEE 754 doubles, as required by the scheme
+            let number = number.as_f64().ok_or_else(|| ManifestError::Json {
+                message: "Number cannot be represented as a double".to_string(),
+            })?;
+            output.push_str(&format_number(number)?);
+        }
+        Value::Array(array) => {
+            output.push('[');
+            for (index, item) in array.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_value(item, output)?;
+            }
+            output.push(']');
+        }
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            output.push('{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&serde_json::to_string(key)?);
+                output.push(':');
+                write_value(item, output)?;
+            }
+            output.push('}');
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats the number like the ECMAScript `Number.prototype.toString` method.
+#[allow(clippy::result_large_err)]
+fn format_number(number: f64) -> Result<String, ManifestError> {
+    if !number.is_finite() {
+        let message = "Non-finite numbers cannot be serialized".to_string();
+        return Err(ManifestError::Json { message });
+    }
+
+    if number == 0.0 {
+        return Ok("0".to_string());
+    }
+
+    // Scientific notation of Rust gives the shortest digits that round-trip
+    let scientific = format!("{:e}", number.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let exponent: i32 = exponent.parse().unwrap_or_default();
+
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut output = String::new();
+    if number < 0.0 {
+        output.push('-');
+    }
+
+    if k <= n && n <= 21 {
+        output.push_str(&digits);
+        output.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        output.push_str(&digits[..n as usize]);
+        output.push('.');
+        output.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        output.push_str("0.");
+        output.push_str(&"0".repeat(-n as usize));
+        output.push_str(&digits);
+    } else {
+        output.push_str(&digits[..1]);
+        if k > 1 {
+            output.push('.');
+            output.push_str(&digits[1..]);
+        }
+        output.push('e');
+        output.push(if n - 1 < 0 { '-' } else { '+' });
+        output.push_str(&(n - 1).abs().to_string());
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use parameterized::parameterized;
+
+    use super::*;
+
+    #[parameterized(
+        number = {
+            0.0, -0.0, 1.0, -1.5, 333333333.3333333, 1e21, 1e20, 1.5e-7, 0.000001, 123.456,
+            9007199254740992.0, 4.5e-322, 1.7976931348623157e308
+        },
+        expected = {
+            "0", "0", "1", "-1.5", "333333333.3333333", "1e+21", "100000000000000000000", "1.5e-7",
+            "0.000001", "123.456", "9007199254740992", "4.5e-322", "1.7976931348623157e+308"
+        },
+    )]
+    fn test_format_number(number: f64, expected: &str) {
+        assert_eq!(format_number(number).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint_bytes(b""), "cbf29ce484222325");
+        assert_eq!(fingerprint_bytes(b"a"), "af63dc4c8601ec8c");
+
+        let first: WebAppManifest =
+            serde_json::from_str(r#"{"name":"Example","short_name":"Ex"}"#).unwrap();
+        let second: WebAppManifest =
+            serde_json::from_str(r#"{"short_name":"Ex","name":"Example"}"#).unwrap();
+        assert_eq!(first.fingerprint().unwrap(), second.fingerprint().unwrap());
+        assert_ne!(first.fingerprint().unwrap(), WebAppManifest::default().fingerprint().unwrap());
+    }
+
+    #[test]
+    fn test_write_value() {
+        let value = serde_json::json!({
+            "b": [1, 2.5, "\u{20ac}\n\u{1f}", null, true],
+            "a": { "\u{e9}": 1, "z": {} },
+            "\u{1f600}": 0,
+            "\u{fb33}": 1,
+        });
+
+        let mut output = String::new();
+        write_value(&value, &mut output).unwrap();
+
+        assert_eq!(output, "{\"a\":{\"z\":{},\"\u{e9}\":1},\"b\":[1,2.5,\"\u{20ac}\\n\\u001f\",null,true],\"\u{1f600}\":0,\"\u{fb33}\":1}");
+    }
+
+    #[test]
+    fn test_to_canonical_json() {
+        let manifest: WebAppManifest =
+            serde_json::from_str(r#"{"short_name":"Ex","name":"Example"}"#).unwrap();
+        let canonical = manifest.to_canonical_json().unwrap();
+        assert_eq!(canonical, r#"{"name":"Example","short_name":"Ex"}"#);
+
+        let manifest: WebAppManifest = serde_json::from_str(r#"{
+            "start_url": "/",
+            "display": "standalone",
+            "dir": "auto",
+            "icons": [{ "src": "icon.png", "sizes": "192x192" }],
+            "shortcuts": [{ "name": "New", "url": "/new", "icons": [{ "src": "new.png", "purpose": "maskable" }] }],
+            "share_target": { "action": "/share", "params": { "title": "title" } }
+        }"#).unwrap();
+        let canonical = manifest.to_canonical_json().unwrap();
+        assert_eq!(
+            canonical,
+            concat!(
+                r#"{"display":"standalone","icons":[{"sizes":"192x192","src":"icon.png"}],"#,
+                r#""share_target":{"action":"/share","params":{"title":"title"}},"#,
+                r#""shortcuts":[{"icons":[{"purpose":"maskable","src":"new.png"}],"name":"New","url":"/new"}],"#,
+                r#""start_url":"/"}"#,
+            )
+        );
+
+        assert_eq!(WebAppManifest::default().to_canonical_json().unwrap(), "{}");
+    }
+}
@@ -0,0 +1,189 @@
+//! Contains support for manifests embedded in `data:` URLs.
+//!
+//! Embedding the manifest in a `data:` URL allows single-page tools to link the
+//! manifest without hosting a separate file. Browsers limit the length of URLs,
+//! so large manifests produce [warnings][DataUrlWarning].
+//!
+//! Relative URLs in manifests embedded in `data:` URLs are resolved against the
+//! document URL when [processing][crate::WebAppManifest::process] the manifest.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use percent_encoding::percent_decode_str;
+
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+/// The media type used for manifests embedded in `data:` URLs.
+pub const MANIFEST_MEDIA_TYPE: &str = "application/manifest+json";
+
+/// The length of the `data:` URL above which some user agents may not load it.
+pub const COMPATIBLE_LENGTH: usize = 32 * 1024;
+
+/// The length of the `data:` URL above which Chromium-based browsers do not load it.
+pub const MAXIMUM_LENGTH: usize = 2 * 1024 * 1024;
+
+/// A warning about the generated `data:` URL.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum DataUrlWarning {
+    /// The URL is longer than [`COMPATIBLE_LENGTH`], so some user agents may not load it.
+    ExceedsCompatibleLength {
+        /// The length of the URL.
+        length: usize,
+    },
+
+    /// The URL is longer than [`MAXIMUM_LENGTH`], so most user agents will not load it.
+    ExceedsMaximumLength {
+        /// The length of the URL.
+        length: usize,
+    },
+}
+
+/// A manifest serialized as a `data:` URL.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DataUrl {
+    /// The serialized `data:` URL.
+    pub url: String,
+
+    /// Warnings about the length of the URL.
+    pub warnings: Vec<DataUrlWarning>,
+}
+
+impl WebAppManifest {
+    /// Serializes the manifest as a base64-encoded `data:` URL.
+    ///
+    /// The URL uses the `application/manifest+json` media type and can be directly
+    /// used in the `href` attribute of the `<link rel="manifest">` element.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The `data:` URL with warnings about its length.
+    /// - `Err`: An error in case the manifest cannot be serialized.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn to_data_url(&self) -> Result<DataUrl, ManifestError> {
+        let json = serde_json::to_vec(self)?;
+        let url = format!("data:{};base64,{}", MANIFEST_MEDIA_TYPE, STANDARD.encode(json));
+
+        let length = url.len();
+        let mut warnings = Vec::new();
+
+        if length > MAXIMUM_LENGTH {
+            warnings.push(DataUrlWarning::ExceedsMaximumLength { length });
+        } else if length > COMPATIBLE_LENGTH {
+            warnings.push(DataUrlWarning::ExceedsCompatibleLength { length });
+        }
+
+        Ok(DataUrl { url, warnings })
+    }
+
+    /// Parses the manifest embedded in a `data:` URL.
+    ///
+    /// Both base64-encoded and percent-encoded URLs are supported. The media type of
+    /// the URL is not checked, as user agents also accept manifests with other types.
+    ///
+    /// # Parameters
+    ///
+    /// - `url`: The `data:` URL, such as `data:application/manifest+json,{"name":"App"}`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The parsed, but not yet processed, manifest.
+    /// - `Err`: An error in case the URL or the manifest cannot be parsed.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::InvalidDataUrl`] if the URL is not a valid `data:` URL.
+    /// - [`ManifestError::Json`] if the embedded manifest is not valid.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn from_data_url(url: &str) -> Result<Self, ManifestError> {
+        let url = url.trim();
+
+        let scheme = url.get(..5).ok_or(ManifestError::InvalidDataUrl)?;
+        if !scheme.eq_ignore_ascii_case("data:") {
+            return Err(ManifestError::InvalidDataUrl);
+        }
+
+        let (header, payload) = url[5..].split_once(',').ok_or(ManifestError::InvalidDataUrl)?;
+        let payload = payload.split('#').next().unwrap_or_default();
+
+        let is_base64 = header
+            .rsplit(';')
+            .next()
+            .map(|parameter| parameter.trim().eq_ignore_ascii_case("base64"))
+            .unwrap_or_default();
+
+        let decoded: Vec<u8> = percent_decode_str(payload).collect();
+
+        let json = if is_base64 {
+            let decoded: Vec<u8> =
+                decoded.into_iter().filter(|byte| !byte.is_ascii_whitespace()).collect();
+            STANDARD.decode(decoded).map_err(|_| ManifestError::InvalidDataUrl)?
+        } else {
+            decoded
+        };
+
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_to_data_url() {
+        let manifest = WebAppManifest { name: Some("Example".to_string()), ..Default::default() };
+        let data_url = manifest.to_data_url().unwrap();
+
+        let prefix = "data:application/manifest+json;base64,";
+        assert!(data_url.url.starts_with(prefix));
+        assert!(data_url.warnings.is_empty());
+
+        let decoded = STANDARD.decode(&data_url.url[prefix.len()..]).unwrap();
+        let deserialized: WebAppManifest = serde_json::from_slice(&decoded).unwrap();
+        assert_eq!(deserialized, manifest);
+    }
+
+    #[test]
+    fn test_to_data_url_length_warning() {
+        let manifest = WebAppManifest {
+            description: Some("a".repeat(COMPATIBLE_LENGTH)),
+            ..Default::default()
+        };
+        let data_url = manifest.to_data_url().unwrap();
+
+        assert_matches!(data_url.warnings[..], [DataUrlWarning::ExceedsCompatibleLength { length }] if length == data_url.url.len());
+    }
+
+    #[test]
+    fn test_from_data_url() {
+        let manifest = WebAppManifest { name: Some("Example".to_string()), ..Default::default() };
+
+        let data_url = manifest.to_data_url().unwrap();
+        assert_eq!(WebAppManifest::from_data_url(&data_url.url).unwrap(), manifest);
+
+        let url = "data:application/manifest+json,{%22name%22:%22Example%22}";
+        assert_eq!(WebAppManifest::from_data_url(url).unwrap(), manifest);
+
+        let url = "DATA:,{\"name\":\"Example\"}";
+        assert_eq!(WebAppManifest::from_data_url(url).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_from_data_url_invalid() {
+        assert_matches!(WebAppManifest::from_data_url("https://example.com/manifest.json"), Err(ManifestError::InvalidDataUrl));
+        assert_matches!(WebAppManifest::from_data_url("data:application/json"), Err(ManifestError::InvalidDataUrl));
+        assert_matches!(WebAppManifest::from_data_url("data:;base64,!!!"), Err(ManifestError::InvalidDataUrl));
+        assert_matches!(WebAppManifest::from_data_url("data:,{"), Err(ManifestError::Json { .. }));
+    }
+}
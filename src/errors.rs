@@ -14,6 +14,50 @@ pub enum ManifestError {
         source: url::ParseError,
     },
 
+    /// When the manifest cannot be serialized to or deserialized from JSON.
+    #[error("Error while processing the manifest JSON: {message}")]
+    Json { message: String },
+
+    /// When the `data:` URL cannot be parsed or decoded.
+    #[error("Provided data URL is not valid")]
+    InvalidDataUrl,
+
+    /// When the manifest signature does not match the manifest or the key.
+    #[error("Provided signature is not valid for the manifest")]
+    InvalidSignature,
+
+    /// When the fetched resource does not match its integrity metadata.
+    #[error("Provided resource does not match its integrity metadata")]
+    IntegrityMismatch,
+
+    /// When the HTTP request cannot be constructed or sent.
+    #[error("Error while fetching the manifest: {message}")]
+    Http { message: String },
+
+    /// When the manifest response has an unsuccessful status code.
+    #[error("Manifest response has an unsuccessful status code ({status})")]
+    HttpStatus { status: u16 },
+
+    /// When the bundle files cannot be read or written.
+    #[error("Error while writing the bundle: {message}")]
+    Io { message: String },
+
+    /// When the source image cannot be decoded or encoded.
+    #[error("Error while processing the image: {message}")]
+    Image { message: String },
+
+    /// When the template placeholder references an undefined variable.
+    #[error("Template variable ({name}) is not defined")]
+    UndefinedVariable { name: String },
+
+    /// When the untrusted manifest exceeds the resource limit.
+    #[error("Manifest exceeds the resource limit ({limit} = {maximum})")]
+    LimitExceeded { limit: &'static str, maximum: usize },
+
+    /// When the overlay profile does not exist.
+    #[error("Profile ({name}) does not exist")]
+    UnknownProfile { name: String },
+
     /// When unknown URL is provided in invalid context (e.g., when not in `scope` or `start_url` fields).
     #[error("Provided unknown URL in invalid context")]
     InvalidUnknownUrl,
@@ -22,15 +66,66 @@ pub enum ManifestError {
     #[error("Provided URLs ({url1}, {url2}) are not in the same origin")]
     NotSameOrigin { url1: url::Url, url2: url::Url },
 
+    /// When the URL has an opaque origin (e.g., `file:`, `data:` or custom scheme URLs),
+    /// and opaque origins are not permitted.
+    #[error("Provided URL ({url}) has an opaque origin")]
+    OpaqueOrigin { url: url::Url },
+
+    /// When the bundle path is absolute or contains parent directory components.
+    #[error("Provided bundle path ({path}) is not valid")]
+    InvalidBundlePath { path: String },
+
+    /// When the application ID contains path separators or parent directory components.
+    #[error("Provided application ID ({app_id}) is not valid")]
+    InvalidAppId { app_id: String },
+
+    /// When the origin contains components other than the scheme, host and port.
+    #[error("Provided origin ({origin}) is not valid")]
+    InvalidOrigin { origin: String },
+
+    /// When the JSON Pointer does not start with `/` and is not empty.
+    #[error("Provided JSON pointer ({pointer}) is not valid")]
+    InvalidPointer { pointer: String },
+
+    /// When the URL pattern uses unsupported or invalid syntax.
+    #[error("Provided URL pattern ({pattern}) is not valid")]
+    InvalidUrlPattern { pattern: String },
+
+    /// When the URL has a dangerous scheme (e.g., `javascript:`) that is not permitted.
+    #[error("Provided URL ({url}) has a dangerous scheme")]
+    DangerousScheme { url: url::Url },
+
+    /// When the URL has a scheme that is not in the list of allowed schemes.
+    #[error("Provided URL ({url}) has a scheme that is not allowed")]
+    DisallowedScheme { url: url::Url },
+
     /// When the URL is not within the scope.
     #[error("Provided URL ({url}) is not within the scope ({scope})")]
     NotWithinScope { url: url::Url, scope: url::Url },
 
+    /// When the same protocol is handled by multiple protocol handlers with different URLs.
+    #[error("Protocol ({protocol}) is handled by multiple protocol handlers")]
+    ConflictingProtocolHandler { protocol: String },
+
+    /// When the manifest must be processed before the operation, but it has not been.
+    #[error("Manifest has not been processed")]
+    NotProcessed,
+
+    /// When multiple errors occurred while processing the manifest.
+    #[error("Multiple errors occurred while processing the manifest ({})", errors.len())]
+    Multiple { errors: Vec<ManifestError> },
+
     /// When the URL cannot be converted to an absolute URL.
-    #[error("Provided URL cannot be converted to an absolute URL")]
+    #[error("Provided URL ({url}) cannot be converted to an absolute URL")]
     NotAbsolute { url: crate::types::Url },
 
     /// When the URL cannot be converted to `String`.
-    #[error("Provided URL cannot be converted to `String`")]
+    #[error("Provided URL ({url}) cannot be converted to `String`")]
     NotStringifyable { url: crate::types::Url },
 }
+
+impl From<serde_json::Error> for ManifestError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json { message: error.to_string() }
+    }
+}
@@ -0,0 +1,155 @@
+//! Contains test fixtures of manifests and their resources.
+//!
+//! The fixtures allow downstream crates to write tests against realistic manifests
+//! without copying JSON documents. All URLs in fixtures are relative, so manifests
+//! need to be [processed][crate::WebAppManifest::process] before using them in tests
+//! that require absolute URLs, for example with `https://example.com/` as a base.
+
+use std::str::FromStr;
+
+use crate::resources::*;
+use crate::types::*;
+use crate::WebAppManifest;
+
+/// Creates a square icon fixture with the source and size.
+pub fn icon(src: &str, size: u32) -> IconResource {
+    IconResource {
+        src: Url::Relative(src.to_string()),
+        r#type: Some(MediaRange::from_str("image/png").expect("media type should be valid")),
+        sizes: [ImageSize::Fixed(size, size)].iter().cloned().collect(),
+        ..Default::default()
+    }
+}
+
+/// Creates a screenshot fixture with the source, size and form factor.
+pub fn screenshot(
+    src: &str,
+    width: u32,
+    height: u32,
+    form_factor: FormFactor,
+) -> ScreenshotResource {
+    ScreenshotResource {
+        src: Url::Relative(src.to_string()),
+        r#type: Some(MediaRange::from_str("image/png").expect("media type should be valid")),
+        sizes: [ImageSize::Fixed(width, height)].iter().cloned().collect(),
+        form_factor: Some(form_factor),
+        label: Some(format!("Screenshot {}", src)),
+        ..Default::default()
+    }
+}
+
+/// Creates a shortcut fixture with the name, URL and a single icon.
+pub fn shortcut(name: &str, url: &str) -> ShortcutResource {
+    ShortcutResource {
+        name: name.to_string(),
+        url: Url::Relative(url.to_string()),
+        icons: vec![icon(&format!("{}.png", name.to_lowercase().replace(' ', "-")), 96)],
+        ..Default::default()
+    }
+}
+
+/// Creates a protocol handler fixture for the protocol and handler URL.
+pub fn protocol_handler(protocol: &str, url: &str) -> ProtocolHandlerResource {
+    ProtocolHandlerResource { protocol: protocol.to_string(), url: Url::Relative(url.to_string()) }
+}
+
+/// Creates a file handler fixture that accepts the media type with the extensions.
+pub fn file_handler(action: &str, media_type: &str, extensions: &[&str]) -> FileHandlerResource {
+    let extensions = extensions.iter().map(|extension| extension.to_string()).collect();
+
+    FileHandlerResource {
+        action: Url::Relative(action.to_string()),
+        accept: vec![(media_type.to_string(), extensions)].into_iter().collect(),
+        ..Default::default()
+    }
+}
+
+/// Creates a related application fixture for the platform and application ID.
+pub fn related_application(platform: Platform, id: &str) -> ExternalApplicationResource {
+    ExternalApplicationResource { platform, id: Some(id.to_string()), ..Default::default() }
+}
+
+impl WebAppManifest {
+    /// Creates a minimal manifest that satisfies the installability criteria of
+    /// common user agents.
+    pub fn example_minimal() -> Self {
+        Self {
+            start_url: Url::Relative("/".to_string()),
+            name: Some("Example App".to_string()),
+            display: Display::Standalone,
+            icons: vec![icon("icon-192.png", 192), icon("icon-512.png", 512)],
+            ..Default::default()
+        }
+    }
+
+    /// Creates a manifest that sets most of the supported members.
+    pub fn example_full() -> Self {
+        Self {
+            start_url: Url::Relative("/app/".to_string()),
+            scope: Url::Relative("/app/".to_string()),
+            name: Some("Example App".to_string()),
+            short_name: Some("Example".to_string()),
+            description: Some("An example web application".to_string()),
+            categories: vec![Category::Productivity, Category::Utilities],
+            keywords: vec!["example".to_string(), "notes".to_string()],
+            dir: Direction::Ltr,
+            lang: Some(LanguageTag::parse("en-US").expect("language tag should be valid")),
+            display: Display::Standalone,
+            display_override: vec![DisplayOverride::WindowControlsOverlay],
+            orientation: Orientation::Any,
+            background_color: Some(Color::new(1.0, 1.0, 1.0, 1.0)),
+            theme_color: Some(Color::new(0.2, 0.4, 0.8, 1.0)),
+            related_applications: vec![related_application(Platform::Play, "com.example.app")],
+            protocol_handlers: vec![protocol_handler("web+example", "/app/protocol?url=%s")],
+            file_handlers: vec![file_handler("/app/open", "text/csv", &[".csv"])],
+            launch_handler: Some(LaunchHandlerResource {
+                client_mode: ClientMode::NavigateExisting.into(),
+            }),
+            shortcuts: vec![shortcut("New Note", "/app/new"), shortcut("Search", "/app/search")],
+            share_target: Some(ShareTargetResource {
+                action: Url::Relative("/app/share".to_string()),
+                params: ShareTargetParams {
+                    title: Some("title".to_string()),
+                    text: Some("text".to_string()),
+                    url: Some("url".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            icons: vec![icon("/app/icon-192.png", 192), icon("/app/icon-512.png", 512)],
+            screenshots: vec![
+                screenshot("/app/narrow.png", 540, 720, FormFactor::Narrow),
+                screenshot("/app/wide.png", 1280, 720, FormFactor::Wide),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_process() {
+        let base = AbsoluteUrl::parse("https://example.com/").unwrap();
+        let document_url = base.join("/app/index.html").unwrap();
+        let manifest_url = base.join("/manifest.webmanifest").unwrap();
+
+        for mut manifest in [WebAppManifest::example_minimal(), WebAppManifest::example_full()] {
+            manifest.process(&document_url, &manifest_url).unwrap();
+            assert!(manifest.validate().is_valid());
+        }
+    }
+
+    #[test]
+    fn test_examples_roundtrip() {
+        for manifest in [WebAppManifest::example_minimal(), WebAppManifest::example_full()] {
+            let serialized = serde_json::to_string(&manifest).unwrap();
+            let deserialized: WebAppManifest = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(deserialized, manifest);
+        }
+    }
+}
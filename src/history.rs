@@ -0,0 +1,373 @@
+//! Contains recording of the manifest history.
+//!
+//! The history stores the first recorded version of the manifest and the changes of
+//! each successive version as [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902)
+//! operations, so any version can be reconstructed or compared with another one
+//! without storing full copies. This enables changelog views in PWA managers.
+
+#![allow(missing_docs)]
+
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+/// A single change between two manifest versions.
+///
+/// Paths are [JSON Pointers](https://www.rfc-editor.org/rfc/rfc6901) into the
+/// serialized manifest, and operations are serialized as JSON Patch operations.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// The member was added.
+    Add { path: String, value: Value },
+
+    /// The member was removed.
+    Remove { path: String },
+
+    /// The value of the member was replaced.
+    Replace { path: String, value: Value },
+}
+
+/// A patch contains all changes of a manifest version compared to the previous version.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ManifestPatch {
+    /// The time when the version was fetched.
+    pub timestamp: SystemTime,
+
+    /// The changes compared to the previous version.
+    pub operations: Vec<PatchOperation>,
+}
+
+/// The history of successive fetched versions of a manifest.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ManifestHistory {
+    base: Value,
+    timestamp: SystemTime,
+    patches: Vec<ManifestPatch>,
+}
+
+impl ManifestHistory {
+    /// Creates a new history with the first fetched version of the manifest.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn new(manifest: &WebAppManifest, timestamp: SystemTime) -> Result<Self, ManifestError> {
+        Ok(Self { base: serde_json::to_value(manifest)?, timestamp, patches: Vec::new() })
+    }
+
+    /// Records a newly fetched version of the manifest.
+    ///
+    /// Versions without any changes compared to the latest version are not recorded.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The recorded patch, or `None` if there are no changes.
+    /// - `Err`: An error in case the manifest cannot be serialized.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    /// - [`ManifestError::InvalidPointer`] if any of the recorded patches is malformed.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn record(
+        &mut self,
+        manifest: &WebAppManifest,
+        timestamp: SystemTime,
+    ) -> Result<Option<&ManifestPatch>, ManifestError> {
+        let latest = self.value(self.len() - 1)?;
+        let current = serde_json::to_value(manifest)?;
+
+        let mut operations = Vec::new();
+        diff_values(&latest, &current, &mut String::new(), &mut operations);
+
+        if operations.is_empty() {
+            return Ok(None);
+        }
+
+        self.patches.push(ManifestPatch { timestamp, operations });
+        Ok(self.patches.last())
+    }
+
+    /// Returns the number of recorded versions.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.patches.len() + 1
+    }
+
+    /// Returns the patches of all recorded versions after the first one.
+    pub fn patches(&self) -> &[ManifestPatch] {
+        &self.patches
+    }
+
+    /// Returns the time when the version was fetched, or `None` if it does not exist.
+    pub fn timestamp(&self, version: usize) -> Option<SystemTime> {
+        match version {
+            0 => Some(self.timestamp),
+            _ => self.patches.get(version - 1).map(|patch| patch.timestamp),
+        }
+    }
+
+    /// Reconstructs the manifest version, or returns `None` if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the version cannot be deserialized.
+    /// - [`ManifestError::InvalidPointer`] if any of the recorded patches is malformed.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn version(&self, version: usize) -> Result<Option<WebAppManifest>, ManifestError> {
+        if version >= self.len() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_value(self.value(version)?)?))
+    }
+
+    /// Compares two versions and returns the changes needed to transform the first
+    /// version into the second one, or `None` if any of them does not exist.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::InvalidPointer`] if any of the recorded patches is malformed.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn compare(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> Result<Option<Vec<PatchOperation>>, ManifestError> {
+        if from >= self.len() || to >= self.len() {
+            return Ok(None);
+        }
+
+        let mut operations = Vec::new();
+        diff_values(&self.value(from)?, &self.value(to)?, &mut String::new(), &mut operations);
+        Ok(Some(operations))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn value(&self, version: usize) -> Result<Value, ManifestError> {
+        let mut value = self.base.clone();
+
+        for patch in &self.patches[..version] {
+            for operation in &patch.operations {
+                apply_operation(&mut value, operation)?;
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+/// Escapes the object key as a JSON Pointer reference token.
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Unescapes the JSON Pointer reference token.
+fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+fn diff_values(old: &Value, new: &Value, path: &mut String, operations: &mut Vec<PatchOperation>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let length = path.len();
+                path.push('/');
+                path.push_str(&escape_token(key));
+
+                match new.get(key) {
+                    Some(new_value) => diff_values(old_value, new_value, path, operations),
+                    None => operations.push(PatchOperation::Remove { path: path.clone() }),
+                }
+
+                path.truncate(length);
+            }
+
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    let path = format!("{}/{}", path, escape_token(key));
+                    operations.push(PatchOperation::Add { path, value: new_value.clone() });
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (index, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                let length = path.len();
+                path.push_str(&format!("/{}", index));
+                diff_values(old_value, new_value, path, operations);
+                path.truncate(length);
+            }
+        }
+        (old, new) if old != new => {
+            operations.push(PatchOperation::Replace { path: path.clone(), value: new.clone() });
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn apply_operation(value: &mut Value, operation: &PatchOperation) -> Result<(), ManifestError> {
+    let (path, new_value) = match operation {
+        PatchOperation::Add { path, value } | PatchOperation::Replace { path, value } => {
+            (path, Some(value))
+        }
+        PatchOperation::Remove { path } => (path, None),
+    };
+
+    if path.is_empty() {
+        if let Some(new_value) = new_value {
+            *value = new_value.clone();
+        }
+        return Ok(());
+    }
+
+    let Some(path) = path.strip_prefix('/') else {
+        return Err(ManifestError::InvalidPointer { pointer: path.clone() });
+    };
+
+    let tokens: Vec<String> = path.split('/').map(unescape_token).collect();
+    let Some((last, parents)) = tokens.split_last() else { unreachable!() };
+
+    let mut target = value;
+    for token in parents {
+        target = match target {
+            Value::Object(object) => {
+                object.entry(token.clone()).or_insert(Value::Object(Map::new()))
+            }
+            Value::Array(array) => match token.parse::<usize>() {
+                Ok(index) if index < array.len() => &mut array[index],
+                _ => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+    }
+
+    match (target, new_value) {
+        (Value::Object(object), Some(new_value)) => {
+            object.insert(last.clone(), new_value.clone());
+        }
+        (Value::Object(object), None) => {
+            object.remove(last);
+        }
+        (Value::Array(array), Some(new_value)) => {
+            if let Some(item) = last.parse::<usize>().ok().and_then(|index| array.get_mut(index)) {
+                *item = new_value.clone();
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::*;
+
+    fn at(seconds: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_record_and_reconstruct() {
+        let first = WebAppManifest { name: Some("Example".to_string()), ..Default::default() };
+        let second = WebAppManifest {
+            name: Some("Example 2".to_string()),
+            display: Display::Standalone,
+            ..first.clone()
+        };
+        let third =
+            WebAppManifest { name: None, categories: vec![Category::News], ..second.clone() };
+
+        let mut history = ManifestHistory::new(&first, at(1)).unwrap();
+        assert!(history.record(&first, at(2)).unwrap().is_none());
+
+        let patch = history.record(&second, at(3)).unwrap().unwrap();
+        assert_eq!(
+            patch.operations,
+            vec![
+                PatchOperation::Replace { path: "/display".to_string(), value: json!("standalone") },
+                PatchOperation::Replace { path: "/name".to_string(), value: json!("Example 2") },
+            ]
+        );
+
+        history.record(&third, at(4)).unwrap();
+        assert_eq!(history.len(), 3);
+
+        assert_eq!(history.version(0).unwrap(), Some(first));
+        assert_eq!(history.version(1).unwrap(), Some(second));
+        assert_eq!(history.version(2).unwrap(), Some(third));
+        assert_eq!(history.version(3).unwrap(), None);
+
+        assert_eq!(history.timestamp(0), Some(at(1)));
+        assert_eq!(history.timestamp(2), Some(at(4)));
+        assert_eq!(history.timestamp(3), None);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        let first = WebAppManifest { name: Some("Example".to_string()), ..Default::default() };
+        let second = WebAppManifest { short_name: Some("Ex".to_string()), ..Default::default() };
+
+        let mut history = ManifestHistory::new(&first, at(1)).unwrap();
+        history.record(&second, at(2)).unwrap();
+
+        assert_eq!(
+            history.compare(1, 0).unwrap().unwrap(),
+            vec![
+                PatchOperation::Remove { path: "/short_name".to_string() },
+                PatchOperation::Add { path: "/name".to_string(), value: json!("Example") },
+            ]
+        );
+
+        assert_eq!(history.compare(0, 0).unwrap().unwrap(), vec![]);
+        assert_eq!(history.compare(0, 2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_patch_serialization() {
+        let operation = PatchOperation::Add { path: "/a~1b".to_string(), value: json!(1) };
+        assert_eq!(serde_json::to_string(&operation).unwrap(), r#"{"op":"add","path":"/a~1b","value":1}"#);
+
+        let mut value = json!({ "a/b": 0 });
+        apply_operation(&mut value, &operation).unwrap();
+        assert_eq!(value, json!({ "a/b": 1 }));
+    }
+
+    #[test]
+    fn test_malformed_pointer() {
+        let mut history = ManifestHistory::new(&WebAppManifest::default(), at(1)).unwrap();
+        history
+            .record(
+                &WebAppManifest { name: Some("Example".to_string()), ..Default::default() },
+                at(2),
+            )
+            .unwrap();
+
+        let mut serialized = serde_json::to_value(&history).unwrap();
+        serialized["patches"][0]["operations"][0]["path"] = json!("name");
+        let history: ManifestHistory = serde_json::from_value(serialized).unwrap();
+
+        assert_matches!(history.version(1), Err(ManifestError::InvalidPointer { pointer }) if pointer == "name");
+        assert_matches!(history.compare(0, 1), Err(ManifestError::InvalidPointer { .. }));
+        assert!(history.version(0).unwrap().is_some());
+    }
+}
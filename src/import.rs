@@ -0,0 +1,175 @@
+//! Contains importers of manifests from other formats.
+
+use serde_json::{Map, Value};
+
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+/// Members whose values are maps with arbitrary keys, such as language tags, feature names
+/// or media types. Keys of these maps are not member names, so they are preserved.
+const MAP_MEMBERS: &[&str] = &["translations", "permissions_policy", "accept"];
+
+/// Converts the camel-case key to the snake-case key used by the manifest.
+fn to_snake_case(key: &str) -> String {
+    let mut output = String::with_capacity(key.len() + 4);
+
+    for char in key.chars() {
+        if char.is_ascii_uppercase() {
+            output.push('_');
+            output.push(char.to_ascii_lowercase());
+        } else {
+            output.push(char);
+        }
+    }
+
+    output
+}
+
+/// Converts numeric icon sizes used by webpack plugins to the manifest format.
+fn convert_sizes(sizes: &Value) -> Value {
+    let size = |value: &Value| match value {
+        Value::Number(number) => Some(format!("{0}x{0}", number)),
+        Value::String(string) => Some(string.clone()),
+        _ => None,
+    };
+
+    let sizes: Vec<String> = match sizes {
+        Value::Array(sizes) => sizes.iter().filter_map(size).collect(),
+        sizes => size(sizes).into_iter().collect(),
+    };
+
+    Value::String(sizes.join(" "))
+}
+
+fn convert_value(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut converted = Map::new();
+
+            for (key, value) in object {
+                let key = to_snake_case(key);
+
+                let value = match key.as_str() {
+                    "sizes" | "size" => convert_sizes(value),
+                    key if MAP_MEMBERS.contains(&key) => convert_map(value),
+                    _ => convert_value(value),
+                };
+
+                // The webpack plugin allows `size` as an alias of `sizes`
+                let key = if key == "size" { "sizes".to_string() } else { key };
+                converted.insert(key, value);
+            }
+
+            Value::Object(converted)
+        }
+        Value::Array(array) => Value::Array(array.iter().map(convert_value).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Converts the values of the map while preserving its keys.
+fn convert_map(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object.iter().map(|(key, value)| (key.clone(), convert_value(value))).collect(),
+        ),
+        value => convert_value(value),
+    }
+}
+
+impl WebAppManifest {
+    /// Imports the manifest from the options of a build tool PWA plugin.
+    ///
+    /// Both the options of `vite-plugin-pwa`, where the manifest is nested in the
+    /// `manifest` option, and the options of webpack plugins such as `webpack-pwa-manifest`,
+    /// where the manifest members are top-level options, are supported. Camel-case keys
+    /// are converted to snake-case member names, numeric icon sizes are converted to the
+    /// manifest format and plugin-specific options are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `options`: The plugin options, converted to JSON.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the converted options are not a valid manifest.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn from_plugin_options(options: &Value) -> Result<Self, ManifestError> {
+        let manifest = match options.get("manifest") {
+            Some(manifest @ Value::Object(_)) => manifest,
+            _ => options,
+        };
+
+        Ok(serde_json::from_value(convert_value(manifest))?)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::*;
+
+    #[test]
+    fn test_import_vite_plugin_options() {
+        let options = json!({
+            "registerType": "autoUpdate",
+            "includeAssets": ["favicon.ico"],
+            "manifest": {
+                "name": "Example App",
+                "shortName": "Example",
+                "themeColor": "#ffffff",
+                "startUrl": "/app/",
+                "preferRelatedApplications": true,
+                "icons": [{ "src": "icon-192.png", "sizes": "192x192", "type": "image/png" }],
+                "fileHandlers": [{ "action": "/open", "accept": { "text/csv": [".csv"], "application/vnd.Example": [".ex"] } }],
+                "permissionsPolicy": { "clipboardRead": ["self"] },
+                "translations": { "en-US": { "shortName": "Ex" } },
+            },
+        });
+
+        let manifest = WebAppManifest::from_plugin_options(&options).unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("Example App"));
+        assert_eq!(manifest.short_name.as_deref(), Some("Example"));
+        assert_eq!(manifest.start_url, Url::Relative("/app/".to_string()));
+        assert!(manifest.prefer_related_applications);
+        assert!(manifest.theme_color.is_some());
+        assert!(manifest.icons[0].sizes.contains(&ImageSize::Fixed(192, 192)));
+        assert!(manifest.file_handlers[0].accept.contains_key("text/csv"));
+        assert!(manifest.file_handlers[0].accept.contains_key("application/vnd.Example"));
+        assert!(manifest.permissions_policy.contains_key("clipboardRead"));
+        assert_eq!(manifest.translations["en-US"].short_name.as_deref(), Some("Ex"));
+    }
+
+    #[test]
+    fn test_import_webpack_plugin_options() {
+        let options = json!({
+            "filename": "manifest.json",
+            "name": "Example App",
+            "background_color": "#000000",
+            "crossorigin": "use-credentials",
+            "icons": [
+                { "src": "icon.png", "sizes": [96, 128, 192], "destination": "icons" },
+                { "src": "large.png", "size": "1024x1024" },
+            ],
+        });
+
+        let manifest = WebAppManifest::from_plugin_options(&options).unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("Example App"));
+        assert_eq!(manifest.icons[0].sizes.len(), 3);
+        assert!(manifest.icons[0].sizes.contains(&ImageSize::Fixed(128, 128)));
+        assert!(manifest.icons[1].sizes.contains(&ImageSize::Fixed(1024, 1024)));
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("shortName"), "short_name");
+        assert_eq!(to_snake_case("short_name"), "short_name");
+    }
+}
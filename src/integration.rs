@@ -0,0 +1,1293 @@
+//! Contains helpers for integrating installed web applications with the operating system.
+//!
+//! These helpers operate on the [processed][crate::WebAppManifest::process] manifest.
+//! Members with URLs that are not absolute are skipped.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::errors::ManifestError;
+use crate::resources::*;
+use crate::types::*;
+use crate::WebAppManifest;
+
+/// The pattern of activation URLs that a route matches.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RoutePattern {
+    /// Matches URLs that are equal to the URL, ignoring their fragments.
+    Url(AbsoluteUrl),
+
+    /// Matches all URLs with the scheme.
+    Scheme(String),
+}
+
+impl RoutePattern {
+    /// Checks whether the activation URL matches the pattern.
+    pub fn matches(&self, url: &AbsoluteUrl) -> bool {
+        match self {
+            Self::Url(pattern) => {
+                let mut url = url.clone();
+                url.set_fragment(None);
+
+                let mut pattern = pattern.clone();
+                pattern.set_fragment(None);
+
+                url == pattern
+            }
+            Self::Scheme(scheme) => url.scheme().eq_ignore_ascii_case(scheme),
+        }
+    }
+}
+
+/// The manifest member that handles activations of a route.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RouteTarget<'a> {
+    /// The route is handled by the shortcut.
+    Shortcut(&'a ShortcutResource),
+
+    /// The route is handled by the protocol handler.
+    ProtocolHandler(&'a ProtocolHandlerResource),
+
+    /// The route is handled by the file handler.
+    FileHandler(&'a FileHandlerResource),
+}
+
+/// A route maps activation URLs to the manifest member that handles them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Route<'a> {
+    /// The pattern of activation URLs handled by the route.
+    pub pattern: RoutePattern,
+
+    /// The manifest member that handles the route.
+    pub target: RouteTarget<'a>,
+}
+
+/// A route table contains all routes of the web application, so native shells can
+/// dispatch incoming activations to the correct manifest member.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RouteTable<'a> {
+    /// All routes in the order of the manifest members.
+    pub routes: Vec<Route<'a>>,
+}
+
+impl<'a> RouteTable<'a> {
+    /// Builds the route table from shortcuts, protocol handlers and file handlers
+    /// of the processed manifest.
+    pub fn from_manifest(manifest: &'a WebAppManifest) -> Self {
+        let mut routes = Vec::new();
+
+        for shortcut in &manifest.shortcuts {
+            if let Url::Absolute(url) = &shortcut.url {
+                let pattern = RoutePattern::Url(url.clone());
+                routes.push(Route { pattern, target: RouteTarget::Shortcut(shortcut) });
+            }
+        }
+
+        for handler in &manifest.protocol_handlers {
+            if let Url::Absolute(_) = &handler.url {
+                let pattern = RoutePattern::Scheme(handler.protocol.to_lowercase());
+                routes.push(Route { pattern, target: RouteTarget::ProtocolHandler(handler) });
+            }
+        }
+
+        for handler in &manifest.file_handlers {
+            if let Url::Absolute(action) = &handler.action {
+                let pattern = RoutePattern::Url(action.clone());
+                routes.push(Route { pattern, target: RouteTarget::FileHandler(handler) });
+            }
+        }
+
+        Self { routes }
+    }
+
+    /// Returns the first route that matches the activation URL.
+    pub fn resolve(&self, url: &AbsoluteUrl) -> Option<&Route<'a>> {
+        self.routes.iter().find(|route| route.pattern.matches(url))
+    }
+}
+
+/// An integration with the operating system that the web application declares.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+pub enum Capability {
+    /// The application can receive shared data.
+    ShareTarget,
+
+    /// The application handles URL protocols.
+    ProtocolHandlers,
+
+    /// The application opens files.
+    FileHandlers,
+
+    /// The application provides shortcuts to its key tasks.
+    Shortcuts,
+
+    /// The application controls how it is launched.
+    LaunchHandler,
+
+    /// The application has related native applications.
+    RelatedApplications,
+
+    /// The application provides widgets to the widgets board.
+    Widgets,
+
+    /// The application can be launched to take a new note.
+    NoteTaking,
+}
+
+/// A set of integrations that the web application declares.
+///
+/// Capabilities are iterated in a stable order, so they can be directly shown
+/// in a summary at install time.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct CapabilitySet(BTreeSet<Capability>);
+
+impl CapabilitySet {
+    /// Collects the capabilities that the manifest declares.
+    pub fn from_manifest(manifest: &WebAppManifest) -> Self {
+        let mut capabilities = BTreeSet::new();
+
+        let declared = [
+            (Capability::ShareTarget, manifest.share_target.is_some()),
+            (Capability::ProtocolHandlers, !manifest.protocol_handlers.is_empty()),
+            (Capability::FileHandlers, !manifest.file_handlers.is_empty()),
+            (Capability::Shortcuts, !manifest.shortcuts.is_empty()),
+            (Capability::LaunchHandler, manifest.launch_handler.is_some()),
+            (Capability::RelatedApplications, !manifest.related_applications.is_empty()),
+            (Capability::Widgets, !manifest.widgets.is_empty()),
+            (
+                Capability::NoteTaking,
+                manifest
+                    .note_taking
+                    .as_ref()
+                    .is_some_and(|note_taking| note_taking.new_note_url.is_some()),
+            ),
+        ];
+
+        for (capability, is_declared) in &declared {
+            if *is_declared {
+                capabilities.insert(*capability);
+            }
+        }
+
+        Self(capabilities)
+    }
+
+    /// Checks whether the capability is declared.
+    pub fn contains(&self, capability: Capability) -> bool {
+        self.0.contains(&capability)
+    }
+
+    /// Returns the number of declared capabilities.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether no capabilities are declared.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the declared capabilities.
+    pub fn iter(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+/// Schemes that may be handled by protocol handlers without the `web+` prefix.
+///
+/// See the [safelisted schemes](https://html.spec.whatwg.org/multipage/system-state.html#safelisted-scheme)
+/// of the HTML specification.
+const SAFELISTED_SCHEMES: &[&str] = &[
+    "bitcoin",
+    "cabal",
+    "dat",
+    "did",
+    "doi",
+    "dweb",
+    "ethereum",
+    "ftp",
+    "geo",
+    "im",
+    "ipfs",
+    "ipns",
+    "irc",
+    "ircs",
+    "magnet",
+    "mailto",
+    "matrix",
+    "mms",
+    "news",
+    "nntp",
+    "openpgp4fpr",
+    "sftp",
+    "sip",
+    "sms",
+    "smsto",
+    "ssb",
+    "ssh",
+    "tel",
+    "urn",
+    "webcal",
+    "wtai",
+    "xmpp",
+];
+
+/// Screen sizes of iOS and iPadOS devices in CSS pixels with their device pixel ratios.
+const APPLE_DEVICES: &[(u32, u32, u32)] = &[
+    (1024, 1366, 2),
+    (834, 1194, 2),
+    (820, 1180, 2),
+    (834, 1112, 2),
+    (810, 1080, 2),
+    (768, 1024, 2),
+    (744, 1133, 2),
+    (430, 932, 3),
+    (393, 852, 3),
+    (428, 926, 3),
+    (390, 844, 3),
+    (414, 896, 3),
+    (414, 896, 2),
+    (375, 812, 3),
+    (414, 736, 3),
+    (375, 667, 2),
+    (320, 568, 2),
+];
+
+/// A splash screen image that iOS displays while the web application is loading.
+///
+/// iOS does not generate splash screens from the manifest, so an image needs to be
+/// provided for each device size and orientation with the `apple-touch-startup-image` link.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AppleSplashScreen<'a> {
+    /// The width of the image in physical pixels.
+    pub width: u32,
+
+    /// The height of the image in physical pixels.
+    pub height: u32,
+
+    /// The media query that matches the device and orientation.
+    pub media: String,
+
+    /// The background color of the image.
+    pub background_color: Color,
+
+    /// The icon that should be drawn in the center of the image, if any.
+    pub icon: Option<&'a IconResource>,
+}
+
+impl AppleSplashScreen<'_> {
+    /// Generates the splash screens for all known devices in both orientations.
+    ///
+    /// The background color is the [effective background color][WebAppManifest::effective_background_color]
+    /// of the manifest, and the icon is the best application icon for a quarter of the shorter
+    /// side of the image.
+    pub fn from_manifest(manifest: &WebAppManifest) -> Vec<AppleSplashScreen<'_>> {
+        let background_color = manifest.effective_background_color();
+        let mut screens = Vec::new();
+
+        for &(width, height, ratio) in APPLE_DEVICES {
+            for &(orientation, landscape) in &[("portrait", false), ("landscape", true)] {
+                let (image_width, image_height) = match landscape {
+                    false => (width * ratio, height * ratio),
+                    true => (height * ratio, width * ratio),
+                };
+
+                let media = format!(
+                    "screen and (device-width: {}px) and (device-height: {}px) and \
+                     (-webkit-device-pixel-ratio: {}) and (orientation: {})",
+                    width, height, ratio, orientation
+                );
+
+                let icon_size = image_width.min(image_height) / 4;
+                let icon = manifest.select_icon(icon_size, 1.0, ImagePurpose::Any);
+
+                screens.push(AppleSplashScreen {
+                    width: image_width,
+                    height: image_height,
+                    media,
+                    background_color: background_color.clone(),
+                    icon,
+                });
+            }
+        }
+
+        screens
+    }
+
+    /// Returns the suggested file name of the image.
+    pub fn file_name(&self) -> String {
+        format!("apple-splash-{}-{}.png", self.width, self.height)
+    }
+
+    /// Returns the HTML link element that references the image at the URL.
+    pub fn link(&self, href: &str) -> String {
+        format!(
+            "<link rel=\"apple-touch-startup-image\" media=\"{}\" href=\"{}\">",
+            escape_xml(&self.media),
+            escape_xml(href)
+        )
+    }
+}
+
+/// The profile of a web application installed in GNOME Web (Epiphany).
+///
+/// Epiphany stores each web application in its own profile directory, which contains
+/// the desktop file, the application icon and an empty `.app` marker file. The caller
+/// is responsible for creating the directory, writing the files and linking the desktop
+/// file to the applications directory.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EpiphanyProfile<'a> {
+    /// The ID of the web application, such as `org.gnome.Epiphany.WebApp_example`.
+    pub id: String,
+
+    /// The profile directory of the web application.
+    pub profile_dir: PathBuf,
+
+    /// The path of the desktop file in the profile directory.
+    pub desktop_file_path: PathBuf,
+
+    /// The contents of the desktop file.
+    pub desktop_file: String,
+
+    /// The path of the application icon in the profile directory.
+    pub icon_path: PathBuf,
+
+    /// The application icon that should be written to the icon path, if any.
+    pub icon: Option<&'a IconResource>,
+
+    /// The path of the empty marker file that identifies the profile as a web application.
+    pub marker_path: PathBuf,
+}
+
+impl EpiphanyProfile<'_> {
+    /// Generates the Epiphany profile from the processed manifest.
+    ///
+    /// # Parameters
+    ///
+    /// - `manifest`: The processed manifest.
+    /// - `app_id`: The unique identifier of the application, which is used in the profile name.
+    /// - `data_dir`: The user data directory, usually `~/.local/share`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::NotProcessed`] if the start URL is not absolute.
+    /// - [`ManifestError::InvalidAppId`] if the application ID is empty or contains path
+    ///   separators or `..`, so the profile would not be within the data directory.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn from_manifest<'a>(
+        manifest: &'a WebAppManifest,
+        app_id: &str,
+        data_dir: &Path,
+    ) -> Result<EpiphanyProfile<'a>, ManifestError> {
+        let Url::Absolute(start_url) = &manifest.start_url else {
+            return Err(ManifestError::NotProcessed);
+        };
+
+        if app_id.is_empty() || app_id.contains(['/', '\\']) || app_id.contains("..") {
+            return Err(ManifestError::InvalidAppId { app_id: app_id.to_string() });
+        }
+
+        let id = format!("org.gnome.Epiphany.WebApp_{}", app_id);
+        let profile_dir = data_dir.join(&id);
+        let desktop_file_path = profile_dir.join(format!("{}.desktop", id));
+        let icon_path = profile_dir.join("app-icon.png");
+        let marker_path = profile_dir.join(".app");
+
+        let name = manifest.name.as_deref().or(manifest.short_name.as_deref()).unwrap_or(&id);
+        let icon = manifest.select_icon(192, 1.0, ImagePurpose::Any);
+
+        let mut desktop_file = String::from("[Desktop Entry]\n");
+        desktop_file.push_str(&format!("Name={}\n", escape_desktop_value(name)));
+        if let Some(description) = &manifest.description {
+            desktop_file.push_str(&format!("Comment={}\n", escape_desktop_value(description)));
+        }
+        desktop_file.push_str(&format!(
+            "Exec=epiphany --application-mode {} {}\n",
+            escape_desktop_value(&quote_exec_argument(&format!(
+                "--profile={}",
+                profile_dir.to_string_lossy()
+            ))),
+            escape_desktop_value(&quote_exec_argument(start_url.as_str())),
+        ));
+        desktop_file.push_str("StartupNotify=true\n");
+        desktop_file.push_str("Terminal=false\n");
+        desktop_file.push_str("Type=Application\n");
+        desktop_file.push_str("Categories=GNOME;GTK;\n");
+        if icon.is_some() {
+            desktop_file.push_str(&format!(
+                "Icon={}\n",
+                escape_desktop_value(&icon_path.to_string_lossy())
+            ));
+        }
+        desktop_file.push_str(&format!("StartupWMClass={}\n", id));
+
+        Ok(EpiphanyProfile {
+            id,
+            profile_dir,
+            desktop_file_path,
+            desktop_file,
+            icon_path,
+            icon,
+            marker_path,
+        })
+    }
+}
+
+/// Quotes the argument of the desktop entry `Exec` key.
+///
+/// Characters that have a special meaning in quoted arguments are escaped with a backslash,
+/// and percent signs are doubled, so they are not interpreted as field codes. The result
+/// still needs to be escaped with [`escape_desktop_value`].
+fn quote_exec_argument(input: &str) -> String {
+    let mut output = String::with_capacity(input.len() + 2);
+    output.push('"');
+
+    for char in input.chars() {
+        match char {
+            '"' | '`' | '$' | '\\' => {
+                output.push('\\');
+                output.push(char);
+            }
+            '%' => output.push_str("%%"),
+            char => output.push(char),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+/// Escapes special characters in the desktop entry value.
+fn escape_desktop_value(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for char in input.chars() {
+        match char {
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            char => output.push(char),
+        }
+    }
+
+    output
+}
+
+/// A Windows registry value that should be written under `HKEY_CURRENT_USER`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RegistryEntry {
+    /// The registry key path, relative to `HKEY_CURRENT_USER`.
+    pub key: String,
+
+    /// The name of the value, or `None` for the default value of the key.
+    pub name: Option<String>,
+
+    /// The string data of the value.
+    pub data: String,
+}
+
+impl RegistryEntry {
+    fn new(key: impl Into<String>, name: Option<&str>, data: impl Into<String>) -> Self {
+        Self { key: key.into(), name: name.map(str::to_string), data: data.into() }
+    }
+}
+
+/// An entry of the `CFBundleURLTypes` array in the macOS `Info.plist` file.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct BundleUrlType {
+    /// The `CFBundleURLName` key, which identifies the URL type.
+    pub name: String,
+
+    /// The `CFBundleURLSchemes` key, which contains the handled URL schemes.
+    pub schemes: Vec<String>,
+}
+
+/// Data needed to register the protocol handlers of the web application with the
+/// operating system.
+///
+/// The caller is responsible for writing the data to the platform-specific locations.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct ProtocolRegistration {
+    /// The handled URL schemes, lower-cased and deduplicated.
+    pub schemes: Vec<String>,
+
+    /// Windows registry entries that register the schemes as URL protocols.
+    pub windows_registry: Vec<RegistryEntry>,
+
+    /// Linux MIME types for the `MimeType` key of the desktop entry.
+    pub linux_mime_types: Vec<String>,
+
+    /// macOS URL types for the `CFBundleURLTypes` key of the `Info.plist` file.
+    pub macos_url_types: Vec<BundleUrlType>,
+}
+
+impl ProtocolRegistration {
+    /// Generates the protocol registration data from protocol handlers of the manifest.
+    ///
+    /// Only the safelisted schemes and schemes that consist of `web+` followed by ASCII
+    /// letters are registered. Protocol handlers with other schemes, such as `https` or
+    /// schemes with characters that are not permitted in registry keys, are skipped.
+    ///
+    /// # Parameters
+    ///
+    /// - `manifest`: The manifest from which the protocol handlers are taken.
+    /// - `command`: The command line that launches the web application on Windows, in which
+    ///   `%1` is replaced by the activation URL.
+    ///
+    pub fn from_manifest(manifest: &WebAppManifest, command: &str) -> Self {
+        let mut schemes: Vec<String> = Vec::new();
+
+        for handler in &manifest.protocol_handlers {
+            let scheme = handler.protocol.to_ascii_lowercase();
+            if is_valid_scheme(&scheme) && !schemes.contains(&scheme) {
+                schemes.push(scheme);
+            }
+        }
+
+        let app_name = manifest.name.as_deref().or(manifest.short_name.as_deref());
+
+        let mut windows_registry = Vec::new();
+        for scheme in &schemes {
+            let key = format!("Software\\Classes\\{}", scheme);
+            let description = format!("URL:{}", app_name.unwrap_or(scheme));
+
+            windows_registry.push(RegistryEntry::new(&key, None, description));
+            windows_registry.push(RegistryEntry::new(&key, Some("URL Protocol"), ""));
+            windows_registry.push(RegistryEntry::new(
+                key + "\\shell\\open\\command",
+                None,
+                command,
+            ));
+        }
+
+        let linux_mime_types =
+            schemes.iter().map(|scheme| format!("x-scheme-handler/{}", scheme)).collect();
+
+        let macos_url_types = schemes
+            .iter()
+            .map(|scheme| BundleUrlType { name: scheme.clone(), schemes: vec![scheme.clone()] })
+            .collect();
+
+        Self { schemes, windows_registry, linux_mime_types, macos_url_types }
+    }
+
+    /// Returns the `MimeType` line of the Linux desktop entry, or `None` if there are
+    /// no handled schemes.
+    pub fn linux_mime_type_line(&self) -> Option<String> {
+        desktop_mime_type_line(&self.linux_mime_types)
+    }
+}
+
+/// An entry of the `CFBundleDocumentTypes` array in the macOS `Info.plist` file.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct BundleDocumentType {
+    /// The `CFBundleTypeName` key, which is the name of the document type.
+    pub name: String,
+
+    /// The `CFBundleTypeMIMETypes` key, which contains the handled media types.
+    pub mime_types: Vec<String>,
+
+    /// The `CFBundleTypeExtensions` key, which contains the handled file extensions
+    /// without leading dots.
+    pub extensions: Vec<String>,
+}
+
+/// Data needed to register the file handlers of the web application with the
+/// operating system.
+///
+/// The caller is responsible for writing the data to the platform-specific locations.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct FileAssociationRegistration {
+    /// Windows registry entries that register a ProgID with the `open` verb for each
+    /// file handler and associate the handled extensions with it.
+    pub windows_registry: Vec<RegistryEntry>,
+
+    /// Linux MIME types for the `MimeType` key of the desktop entry.
+    pub linux_mime_types: Vec<String>,
+
+    /// Linux shared MIME-info XML that defines globs for the handled extensions, or
+    /// `None` if there are no concrete media types with extensions.
+    pub linux_mime_info: Option<String>,
+
+    /// macOS document types for the `CFBundleDocumentTypes` key of the `Info.plist` file.
+    pub macos_document_types: Vec<BundleDocumentType>,
+}
+
+impl FileAssociationRegistration {
+    /// Generates the file association data from file handlers of the manifest.
+    ///
+    /// Media types that are not in the `type/subtype` form and extensions that contain
+    /// characters other than ASCII letters and digits are skipped, so they cannot inject
+    /// registry keys or desktop entry values.
+    ///
+    /// # Parameters
+    ///
+    /// - `manifest`: The manifest from which the file handlers are taken.
+    /// - `app_id`: The identifier of the installed application, which is used as a prefix
+    ///   of the Windows ProgIDs.
+    /// - `command`: The command line that launches the web application on Windows, in which
+    ///   `%1` is replaced by the path of the opened file.
+    ///
+    pub fn from_manifest(manifest: &WebAppManifest, app_id: &str, command: &str) -> Self {
+        let app_name = manifest.name.as_deref().or(manifest.short_name.as_deref());
+
+        let mut registration = Self::default();
+        let mut mime_info = String::new();
+
+        for (index, handler) in manifest.file_handlers.iter().enumerate() {
+            let name = handler.name.as_deref().or(app_name).unwrap_or(app_id).to_string();
+            let prog_id = format!("{}.FileHandler{}", app_id, index + 1);
+            let key = format!("Software\\Classes\\{}", prog_id);
+
+            let registry = &mut registration.windows_registry;
+            registry.push(RegistryEntry::new(&key, None, &name));
+            registry.push(RegistryEntry::new(key + "\\shell\\open\\command", None, command));
+
+            let mut document_type =
+                BundleDocumentType { name, mime_types: Vec::new(), extensions: Vec::new() };
+
+            for (media_type, extensions) in &handler.accept {
+                if !is_valid_media_type(media_type) {
+                    continue;
+                }
+
+                let extensions: Vec<_> = extensions
+                    .iter()
+                    .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+                    .filter(|extension| is_valid_extension(extension))
+                    .collect();
+
+                for extension in &extensions {
+                    let key = format!("Software\\Classes\\.{}\\OpenWithProgids", extension);
+                    registration.windows_registry.push(RegistryEntry::new(key, Some(&prog_id), ""));
+
+                    if !document_type.extensions.contains(extension) {
+                        document_type.extensions.push(extension.clone());
+                    }
+                }
+
+                if !registration.linux_mime_types.contains(media_type) {
+                    registration.linux_mime_types.push(media_type.clone());
+                }
+
+                document_type.mime_types.push(media_type.clone());
+
+                if !media_type.contains('*') && !extensions.is_empty() {
+                    mime_info
+                        .push_str(&format!("  <mime-type type=\"{}\">\n", escape_xml(media_type)));
+                    for extension in &extensions {
+                        mime_info.push_str(&format!(
+                            "    <glob pattern=\"*.{}\"/>\n",
+                            escape_xml(extension)
+                        ));
+                    }
+                    mime_info.push_str("  </mime-type>\n");
+                }
+            }
+
+            registration.macos_document_types.push(document_type);
+        }
+
+        if !mime_info.is_empty() {
+            registration.linux_mime_info = Some(format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n\
+                 {}</mime-info>\n",
+                mime_info
+            ));
+        }
+
+        registration
+    }
+
+    /// Returns the `MimeType` line of the Linux desktop entry, or `None` if there are
+    /// no handled media types.
+    pub fn linux_mime_type_line(&self) -> Option<String> {
+        desktop_mime_type_line(&self.linux_mime_types)
+    }
+}
+
+/// Data needed to register the web application as a system share target.
+///
+/// The caller is responsible for inserting the snippets into the platform-specific
+/// application manifests.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct ShareTargetRegistration {
+    /// Media types of the accepted share data. Shared titles, texts and URLs are
+    /// accepted as `text/plain`.
+    pub media_types: Vec<String>,
+
+    /// File extensions of the accepted files without leading dots.
+    pub file_extensions: Vec<String>,
+
+    /// Android `<intent-filter>` elements for the `AndroidManifest.xml` file.
+    pub android_intent_filters: String,
+
+    /// Windows `windows.shareTarget` extension for the `AppxManifest.xml` file.
+    pub windows_share_target: String,
+}
+
+impl ShareTargetRegistration {
+    /// Generates the share target registration data from the share target of the manifest.
+    ///
+    /// # Returns
+    ///
+    /// - `Some`: The registration data if the manifest has a share target.
+    /// - `None`: If the manifest does not have a share target.
+    ///
+    pub fn from_manifest(manifest: &WebAppManifest) -> Option<Self> {
+        let share_target = manifest.share_target.as_ref()?;
+        let params = &share_target.params;
+
+        let accepts_text = params.title.is_some() || params.text.is_some() || params.url.is_some();
+
+        let mut file_media_types: Vec<String> = Vec::new();
+        let mut file_extensions: Vec<String> = Vec::new();
+
+        for accepted in params.files.iter().flat_map(|files| &files.accept) {
+            if let Some(extension) = accepted.strip_prefix('.') {
+                let extension = extension.to_lowercase();
+                if !extension.is_empty() && !file_extensions.contains(&extension) {
+                    file_extensions.push(extension);
+                }
+            } else if accepted.contains('/') {
+                let media_type = accepted.to_lowercase();
+                if !file_media_types.contains(&media_type) {
+                    file_media_types.push(media_type);
+                }
+            }
+        }
+
+        let mut media_types = Vec::new();
+        if accepts_text {
+            media_types.push("text/plain".to_string());
+        }
+        for media_type in &file_media_types {
+            if !media_types.contains(media_type) {
+                media_types.push(media_type.clone());
+            }
+        }
+
+        let mut android = String::new();
+        let mut filter = |action: &str, media_types: &[String]| {
+            android.push_str("<intent-filter>\n");
+            android.push_str(&format!(
+                "    <action android:name=\"android.intent.action.{}\" />\n",
+                action
+            ));
+            android.push_str("    <category android:name=\"android.intent.category.DEFAULT\" />\n");
+            for media_type in media_types {
+                android.push_str(&format!(
+                    "    <data android:mimeType=\"{}\" />\n",
+                    escape_xml(media_type)
+                ));
+            }
+            android.push_str("</intent-filter>\n");
+        };
+
+        if !media_types.is_empty() {
+            filter("SEND", &media_types);
+        }
+        if !file_media_types.is_empty() {
+            filter("SEND_MULTIPLE", &file_media_types);
+        }
+
+        let mut windows = String::new();
+        windows.push_str("<uap:Extension Category=\"windows.shareTarget\">\n");
+        windows.push_str("  <uap:ShareTarget>\n");
+        if !params.files.is_empty() {
+            windows.push_str("    <uap:SupportedFileTypes>\n");
+            if !file_media_types.is_empty() || file_extensions.is_empty() {
+                windows.push_str("      <uap:SupportsAnyFileType />\n");
+            } else {
+                for extension in &file_extensions {
+                    windows.push_str(&format!(
+                        "      <uap:FileType>.{}</uap:FileType>\n",
+                        escape_xml(extension)
+                    ));
+                }
+            }
+            windows.push_str("    </uap:SupportedFileTypes>\n");
+        }
+        if params.title.is_some() || params.text.is_some() {
+            windows.push_str("    <uap:DataFormat>Text</uap:DataFormat>\n");
+        }
+        if params.url.is_some() {
+            windows.push_str("    <uap:DataFormat>Uri</uap:DataFormat>\n");
+        }
+        if !params.files.is_empty() {
+            windows.push_str("    <uap:DataFormat>StorageItems</uap:DataFormat>\n");
+        }
+        windows.push_str("  </uap:ShareTarget>\n");
+        windows.push_str("</uap:Extension>\n");
+
+        Some(Self {
+            media_types,
+            file_extensions,
+            android_intent_filters: android,
+            windows_share_target: windows,
+        })
+    }
+}
+
+/// Checks whether the lowercase scheme may be registered by protocol handlers.
+fn is_valid_scheme(scheme: &str) -> bool {
+    match scheme.strip_prefix("web+") {
+        Some(name) => !name.is_empty() && name.bytes().all(|byte| byte.is_ascii_lowercase()),
+        None => SAFELISTED_SCHEMES.contains(&scheme),
+    }
+}
+
+/// Checks whether the media type has the `type/subtype` form, where both parts consist
+/// of the restricted name characters of RFC 6838 or are wildcards.
+fn is_valid_media_type(media_type: &str) -> bool {
+    let is_valid_name = |name: &str| {
+        name == "*"
+            || (name.bytes().next().is_some_and(|byte| byte.is_ascii_alphanumeric())
+                && name
+                    .bytes()
+                    .all(|byte| byte.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(&byte)))
+    };
+
+    match media_type.split_once('/') {
+        Some((r#type, subtype)) => is_valid_name(r#type) && is_valid_name(subtype),
+        None => false,
+    }
+}
+
+/// Checks whether the file extension without the leading dot consists only of ASCII
+/// letters and digits.
+fn is_valid_extension(extension: &str) -> bool {
+    !extension.is_empty() && extension.bytes().all(|byte| byte.is_ascii_alphanumeric())
+}
+
+/// Escapes special characters in XML text and attribute values.
+pub(crate) fn escape_xml(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for char in input.chars() {
+        match char {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            char => output.push(char),
+        }
+    }
+
+    output
+}
+
+/// Formats MIME types as the `MimeType` line of the desktop entry.
+fn desktop_mime_type_line(mime_types: &[String]) -> Option<String> {
+    if mime_types.is_empty() {
+        return None;
+    }
+
+    let mime_types: String = mime_types.iter().map(|mime_type| format!("{};", mime_type)).collect();
+    Some(format!("MimeType={}", mime_types))
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_route_table() {
+        let url = |url: &str| AbsoluteUrl::parse(url).unwrap();
+
+        let manifest = WebAppManifest {
+            shortcuts: vec![
+                ShortcutResource {
+                    url: Url::Absolute(url("https://example.com/new")),
+                    ..Default::default()
+                },
+                ShortcutResource {
+                    url: Url::Relative("/unprocessed".to_string()),
+                    ..Default::default()
+                },
+            ],
+            protocol_handlers: vec![ProtocolHandlerResource {
+                protocol: "web+Example".to_string(),
+                url: Url::Absolute(url("https://example.com/protocol?url=%s")),
+            }],
+            file_handlers: vec![FileHandlerResource {
+                action: Url::Absolute(url("https://example.com/open")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let table = manifest.route_table();
+        assert_eq!(table.routes.len(), 3);
+
+        let route = table.resolve(&url("https://example.com/new#section")).unwrap();
+        assert_matches!(route.target, RouteTarget::Shortcut(shortcut) if shortcut == &manifest.shortcuts[0]);
+
+        let route = table.resolve(&url("web+example:item/1")).unwrap();
+        assert_matches!(route.target, RouteTarget::ProtocolHandler(_));
+
+        let route = table.resolve(&url("https://example.com/open")).unwrap();
+        assert_matches!(route.target, RouteTarget::FileHandler(_));
+
+        assert!(table.resolve(&url("https://example.com/unprocessed")).is_none());
+        assert!(table.resolve(&url("https://example.com/new?query")).is_none());
+    }
+
+    #[test]
+    fn test_protocol_registration() {
+        let handler = |protocol: &str| ProtocolHandlerResource {
+            protocol: protocol.to_string(),
+            url: Url::Relative("/protocol?url=%s".to_string()),
+        };
+
+        let manifest = WebAppManifest {
+            name: Some("Example".to_string()),
+            protocol_handlers: vec![
+                handler("web+example"),
+                handler("mailto"),
+                handler("WEB+EXAMPLE"),
+                handler("https"),
+                handler("web+a\\..\\x"),
+                handler("web+"),
+                handler("web+ex4mple"),
+            ],
+            ..Default::default()
+        };
+
+        let registration = manifest.protocol_registration(r#""C:\Example\app.exe" "%1""#);
+        assert_eq!(registration.schemes, vec!["web+example", "mailto"]);
+
+        assert_eq!(registration.windows_registry.len(), 6);
+        assert_eq!(registration.windows_registry[0], RegistryEntry::new("Software\\Classes\\web+example", None, "URL:Example"));
+        assert_eq!(registration.windows_registry[1], RegistryEntry::new("Software\\Classes\\web+example", Some("URL Protocol"), ""));
+        assert_eq!(
+            registration.windows_registry[2],
+            RegistryEntry::new("Software\\Classes\\web+example\\shell\\open\\command", None, r#""C:\Example\app.exe" "%1""#)
+        );
+
+        assert_eq!(
+            registration.linux_mime_type_line().as_deref(),
+            Some("MimeType=x-scheme-handler/web+example;x-scheme-handler/mailto;")
+        );
+
+        assert_eq!(registration.macos_url_types[1].name, "mailto");
+        assert_eq!(registration.macos_url_types[1].schemes, vec!["mailto"]);
+
+        let manifest: WebAppManifest = serde_json::from_str(
+            r#"{
+            "protocol_handlers": [
+                { "protocol": "https", "url": "/protocol?url=%s" },
+                { "protocol": "web+a\\..\\x", "url": "/protocol?url=%s" }
+            ]
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.protocol_registration("app"), ProtocolRegistration::default());
+
+        let registration = WebAppManifest::default().protocol_registration("app");
+        assert_eq!(registration, ProtocolRegistration::default());
+        assert!(registration.linux_mime_type_line().is_none());
+    }
+
+    #[test]
+    fn test_file_association_registration() {
+        let manifest = WebAppManifest {
+            name: Some("Example".to_string()),
+            file_handlers: vec![
+                FileHandlerResource {
+                    action: Url::Relative("/open-csv".to_string()),
+                    name: Some("Spreadsheet".to_string()),
+                    accept: vec![(
+                        "text/csv".to_string(),
+                        vec![".csv".to_string(), ".TSV".to_string()],
+                    )]
+                    .into_iter()
+                    .collect(),
+                    ..Default::default()
+                },
+                FileHandlerResource {
+                    action: Url::Relative("/open-image".to_string()),
+                    accept: vec![("image/*".to_string(), vec![".png".to_string()])]
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let registration = manifest.file_association_registration("Example.App", "app.exe \"%1\"");
+
+        let registry = &registration.windows_registry;
+        assert_eq!(registry[0], RegistryEntry::new("Software\\Classes\\Example.App.FileHandler1", None, "Spreadsheet"));
+        assert_eq!(registry[1], RegistryEntry::new("Software\\Classes\\Example.App.FileHandler1\\shell\\open\\command", None, "app.exe \"%1\""));
+        assert_eq!(registry[2], RegistryEntry::new("Software\\Classes\\.csv\\OpenWithProgids", Some("Example.App.FileHandler1"), ""));
+        assert_eq!(registry[3], RegistryEntry::new("Software\\Classes\\.tsv\\OpenWithProgids", Some("Example.App.FileHandler1"), ""));
+        assert_eq!(registry[4], RegistryEntry::new("Software\\Classes\\Example.App.FileHandler2", None, "Example"));
+        assert_eq!(registry.len(), 7);
+
+        assert_eq!(registration.linux_mime_type_line().as_deref(), Some("MimeType=text/csv;image/*;"));
+        assert_eq!(
+            registration.linux_mime_info.as_deref(),
+            Some(concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n",
+                "  <mime-type type=\"text/csv\">\n",
+                "    <glob pattern=\"*.csv\"/>\n",
+                "    <glob pattern=\"*.tsv\"/>\n",
+                "  </mime-type>\n",
+                "</mime-info>\n",
+            ))
+        );
+
+        let document_types = &registration.macos_document_types;
+        assert_eq!(document_types.len(), 2);
+        assert_eq!(document_types[0].name, "Spreadsheet");
+        assert_eq!(document_types[0].mime_types, vec!["text/csv"]);
+        assert_eq!(document_types[0].extensions, vec!["csv", "tsv"]);
+        assert_eq!(document_types[1].name, "Example");
+    }
+
+    #[test]
+    fn test_file_association_registration_invalid() {
+        let manifest: WebAppManifest = serde_json::from_str(
+            r#"{
+            "file_handlers": [{
+                "action": "/open",
+                "accept": {
+                    "text/csv": [".csv", ".c\\..\\x", ".tsv;", "..", ".txt "],
+                    "text/plain;charset=utf-8": [".txt"],
+                    "text/x\\y": [".xy"],
+                    "text": [".text"]
+                }
+            }]
+        }"#,
+        )
+        .unwrap();
+
+        let registration = manifest.file_association_registration("Example.App", "app.exe \"%1\"");
+
+        let keys: Vec<_> =
+            registration.windows_registry.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, [
+            "Software\\Classes\\Example.App.FileHandler1",
+            "Software\\Classes\\Example.App.FileHandler1\\shell\\open\\command",
+            "Software\\Classes\\.csv\\OpenWithProgids",
+            "Software\\Classes\\.txt\\OpenWithProgids",
+        ]);
+
+        assert_eq!(registration.linux_mime_type_line().as_deref(), Some("MimeType=text/csv;"));
+        assert_eq!(registration.macos_document_types[0].extensions, vec!["csv", "txt"]);
+    }
+
+    #[test]
+    fn test_share_target_registration() {
+        let manifest = WebAppManifest {
+            share_target: Some(ShareTargetResource {
+                action: Url::Relative("/share".to_string()),
+                params: ShareTargetParams {
+                    title: Some("title".to_string()),
+                    url: Some("url".to_string()),
+                    files: vec![ShareTargetFiles {
+                        name: "images".to_string(),
+                        accept: vec!["image/*".to_string(), ".PNG".to_string()],
+                    }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let registration = manifest.share_target_registration().unwrap();
+        assert_eq!(registration.media_types, vec!["text/plain", "image/*"]);
+        assert_eq!(registration.file_extensions, vec!["png"]);
+
+        assert_eq!(
+            registration.android_intent_filters,
+            concat!(
+                "<intent-filter>\n",
+                "    <action android:name=\"android.intent.action.SEND\" />\n",
+                "    <category android:name=\"android.intent.category.DEFAULT\" />\n",
+                "    <data android:mimeType=\"text/plain\" />\n",
+                "    <data android:mimeType=\"image/*\" />\n",
+                "</intent-filter>\n",
+                "<intent-filter>\n",
+                "    <action android:name=\"android.intent.action.SEND_MULTIPLE\" />\n",
+                "    <category android:name=\"android.intent.category.DEFAULT\" />\n",
+                "    <data android:mimeType=\"image/*\" />\n",
+                "</intent-filter>\n",
+            )
+        );
+
+        assert_eq!(
+            registration.windows_share_target,
+            concat!(
+                "<uap:Extension Category=\"windows.shareTarget\">\n",
+                "  <uap:ShareTarget>\n",
+                "    <uap:SupportedFileTypes>\n",
+                "      <uap:SupportsAnyFileType />\n",
+                "    </uap:SupportedFileTypes>\n",
+                "    <uap:DataFormat>Text</uap:DataFormat>\n",
+                "    <uap:DataFormat>Uri</uap:DataFormat>\n",
+                "    <uap:DataFormat>StorageItems</uap:DataFormat>\n",
+                "  </uap:ShareTarget>\n",
+                "</uap:Extension>\n",
+            )
+        );
+
+        assert!(WebAppManifest::default().share_target_registration().is_none());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        assert!(WebAppManifest::default().capabilities().is_empty());
+
+        let manifest = WebAppManifest {
+            shortcuts: vec![ShortcutResource::default()],
+            share_target: Some(ShareTargetResource::default()),
+            protocol_handlers: vec![ProtocolHandlerResource::default()],
+            ..Default::default()
+        };
+
+        let capabilities = manifest.capabilities();
+        assert_eq!(capabilities.len(), 3);
+        assert!(capabilities.contains(Capability::Shortcuts));
+        assert!(!capabilities.contains(Capability::FileHandlers));
+        assert_eq!(
+            capabilities.iter().collect::<Vec<_>>(),
+            vec![Capability::ShareTarget, Capability::ProtocolHandlers, Capability::Shortcuts]
+        );
+
+        let manifest = WebAppManifest {
+            note_taking: Some(NoteTakingResource { new_note_url: None }),
+            ..Default::default()
+        };
+        assert!(!manifest.capabilities().contains(Capability::NoteTaking));
+
+        let manifest = WebAppManifest {
+            note_taking: Some(NoteTakingResource {
+                new_note_url: Some(Url::Relative("/new".to_string())),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(manifest.capabilities().iter().collect::<Vec<_>>(), vec![Capability::NoteTaking]);
+    }
+
+    #[test]
+    fn test_apple_splash_screens() {
+        let manifest = WebAppManifest {
+            background_color: Some(Color::new(1.0, 0.0, 0.0, 1.0)),
+            icons: vec![
+                IconResource {
+                    src: Url::Relative("icon-192.png".to_string()),
+                    sizes: [ImageSize::Fixed(192, 192)].iter().cloned().collect(),
+                    ..Default::default()
+                },
+                IconResource {
+                    src: Url::Relative("icon-512.png".to_string()),
+                    sizes: [ImageSize::Fixed(512, 512)].iter().cloned().collect(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let screens = manifest.apple_splash_screens();
+        assert_eq!(screens.len(), APPLE_DEVICES.len() * 2);
+
+        let portrait = &screens[0];
+        assert_eq!((portrait.width, portrait.height), (2048, 2732));
+        assert_eq!(portrait.background_color.to_rgba8(), [255, 0, 0, 255]);
+        assert_eq!(portrait.icon.unwrap().src, Url::Relative("icon-512.png".to_string()));
+        assert_eq!(portrait.file_name(), "apple-splash-2048-2732.png");
+        assert_eq!(
+            portrait.link("/splash/apple-splash-2048-2732.png"),
+            concat!(
+                r#"<link rel="apple-touch-startup-image" media="screen and (device-width: 1024px) and "#,
+                r#"(device-height: 1366px) and (-webkit-device-pixel-ratio: 2) and (orientation: portrait)" "#,
+                r#"href="/splash/apple-splash-2048-2732.png">"#,
+            )
+        );
+
+        let landscape = &screens[1];
+        assert_eq!((landscape.width, landscape.height), (2732, 2048));
+        assert!(landscape.media.ends_with("(orientation: landscape)"));
+
+        let small = screens.last().unwrap();
+        assert_eq!((small.width, small.height), (1136, 640));
+        assert_eq!(small.icon.unwrap().src, Url::Relative("icon-192.png".to_string()));
+    }
+
+    #[test]
+    fn test_epiphany_profile() {
+        let manifest = WebAppManifest {
+            start_url: Url::Absolute(AbsoluteUrl::parse("https://example.com/app/").unwrap()),
+            name: Some("Example App".to_string()),
+            description: Some("First line\nSecond line".to_string()),
+            icons: vec![IconResource {
+                src: Url::Relative("icon.png".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let data_dir = Path::new("/home/user/.local/share");
+        let profile = manifest.epiphany_profile("example", data_dir).unwrap();
+
+        let profile_dir = data_dir.join("org.gnome.Epiphany.WebApp_example");
+        assert_eq!(profile.id, "org.gnome.Epiphany.WebApp_example");
+        assert_eq!(profile.desktop_file_path, profile_dir.join("org.gnome.Epiphany.WebApp_example.desktop"));
+        assert_eq!(profile.icon_path, profile_dir.join("app-icon.png"));
+        assert_eq!(profile.marker_path, profile_dir.join(".app"));
+        assert_eq!(profile.icon, Some(&manifest.icons[0]));
+
+        assert_eq!(
+            profile.desktop_file,
+            concat!(
+                "[Desktop Entry]\n",
+                "Name=Example App\n",
+                "Comment=First line\\nSecond line\n",
+                "Exec=epiphany --application-mode \"--profile=/home/user/.local/share/org.gnome.Epiphany.WebApp_example\" \"https://example.com/app/\"\n",
+                "StartupNotify=true\n",
+                "Terminal=false\n",
+                "Type=Application\n",
+                "Categories=GNOME;GTK;\n",
+                "Icon=/home/user/.local/share/org.gnome.Epiphany.WebApp_example/app-icon.png\n",
+                "StartupWMClass=org.gnome.Epiphany.WebApp_example\n",
+            )
+        );
+
+        let unprocessed = WebAppManifest::default();
+        assert_matches!(unprocessed.epiphany_profile("example", data_dir), Err(ManifestError::NotProcessed));
+
+        for app_id in &["", "../example", "example/app", "example\\app", ".."] {
+            assert_matches!(manifest.epiphany_profile(app_id, data_dir), Err(ManifestError::InvalidAppId { .. }));
+        }
+    }
+
+    #[test]
+    fn test_epiphany_profile_exec_quoting() {
+        let manifest = WebAppManifest {
+            start_url: Url::Absolute(AbsoluteUrl::parse("https://x/?a=1&b=%20").unwrap()),
+            ..Default::default()
+        };
+
+        let profile =
+            manifest.epiphany_profile("example", Path::new("/home/$user/\"data\"")).unwrap();
+        let exec = profile.desktop_file.lines().find(|line| line.starts_with("Exec=")).unwrap();
+
+        assert_eq!(
+            exec,
+            r#"Exec=epiphany --application-mode "--profile=/home/\\$user/\\"data\\"/org.gnome.Epiphany.WebApp_example" "https://x/?a=1&b=%%20""#
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Contains computation and verification of subresource integrity metadata.
+//!
+//! The metadata is stored in the `integrity` field of icons and screenshots. It
+//! should be computed when the image is first fetched and verified whenever it is
+//! fetched again, so cached images can be trusted across runs.
+//!
+//! # See also
+//!
+//! - [Subresource Integrity](https://w3c.github.io/webappsec-subresource-integrity/)
+//!
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::errors::ManifestError;
+use crate::resources::{IconResource, ScreenshotResource};
+
+/// A hash algorithm supported in the integrity metadata.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+pub enum IntegrityAlgorithm {
+    /// The SHA-256 hash algorithm.
+    Sha256,
+
+    /// The SHA-384 hash algorithm.
+    ///
+    /// This is the default variant.
+    Sha384,
+
+    /// The SHA-512 hash algorithm.
+    Sha512,
+}
+
+impl Default for IntegrityAlgorithm {
+    #[inline]
+    fn default() -> Self {
+        Self::Sha384
+    }
+}
+
+impl IntegrityAlgorithm {
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix.to_ascii_lowercase().as_str() {
+            "sha256" => Some(Self::Sha256),
+            "sha384" => Some(Self::Sha384),
+            "sha512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn digest(self, bytes: &[u8]) -> String {
+        let digest = match self {
+            Self::Sha256 => Sha256::digest(bytes).to_vec(),
+            Self::Sha384 => Sha384::digest(bytes).to_vec(),
+            Self::Sha512 => Sha512::digest(bytes).to_vec(),
+        };
+
+        STANDARD.encode(digest)
+    }
+}
+
+/// Computes the integrity metadata of the resource, such as `sha384-...`.
+pub fn compute_integrity(bytes: &[u8], algorithm: IntegrityAlgorithm) -> String {
+    format!("{}-{}", algorithm.prefix(), algorithm.digest(bytes))
+}
+
+/// Checks whether the resource matches the integrity metadata.
+///
+/// As defined by the specification, the metadata may contain multiple space-separated
+/// hashes, from which only the hashes with the strongest supported algorithm are used.
+/// The resource matches if it matches any of them.
+///
+/// Unlike in browsers, metadata without any supported hashes does not match any resource,
+/// so stored metadata that cannot be verified is never trusted.
+pub fn matches_integrity(metadata: &str, bytes: &[u8]) -> bool {
+    let hashes: Vec<_> = metadata
+        .split_ascii_whitespace()
+        .filter_map(|hash| {
+            // Options after `?` are reserved for future use and ignored
+            let hash = hash.split('?').next().unwrap_or_default();
+            let (prefix, digest) = hash.split_once('-')?;
+            Some((IntegrityAlgorithm::from_prefix(prefix)?, digest))
+        })
+        .collect();
+
+    let Some(strongest) = hashes.iter().map(|(algorithm, _)| *algorithm).max() else {
+        return false;
+    };
+
+    let actual = strongest.digest(bytes);
+    hashes.iter().any(|(algorithm, digest)| *algorithm == strongest && *digest == actual)
+}
+
+macro_rules! impl_integrity {
+    ($resource:ty) => {
+        impl $resource {
+            /// Computes the integrity metadata of the fetched image and stores it in
+            /// the `integrity` field.
+            pub fn compute_integrity(&mut self, bytes: &[u8], algorithm: IntegrityAlgorithm) {
+                self.integrity = Some(compute_integrity(bytes, algorithm));
+            }
+
+            /// Verifies the re-fetched image against the stored integrity metadata.
+            ///
+            /// Images without integrity metadata are always accepted.
+            ///
+            /// # Errors
+            ///
+            /// - [`ManifestError::IntegrityMismatch`] if the image does not match the metadata.
+            ///
+            #[allow(clippy::result_large_err)]
+            pub fn verify_integrity(&self, bytes: &[u8]) -> Result<(), ManifestError> {
+                match &self.integrity {
+                    Some(metadata) if !matches_integrity(metadata, bytes) => {
+                        Err(ManifestError::IntegrityMismatch)
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+    };
+}
+
+impl_integrity!(IconResource);
+impl_integrity!(ScreenshotResource);
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+    use parameterized::parameterized;
+
+    use super::*;
+
+    #[parameterized(
+        algorithm = { IntegrityAlgorithm::Sha256, IntegrityAlgorithm::Sha384, IntegrityAlgorithm::Sha512 },
+        expected = {
+            "sha256-LCa0a2j/xo/5m0U8HTBBNBNCLXBkg7+g+YpeiGJm564=",
+            "sha384-mMEf/f3VQGdrGhN8saIrKnA1DJpEFx1rEYDGvly7LuP3nVMsih3Z7y6OCOdSo7q7",
+            "sha512-9/u6bgY2+JDlb7vzKD5STG+jIErimDgtYkdB0NxmODJuKCxBvl5CVNiCB3LFUYosWowMf37aGVlKfrU5RT4e1w==",
+        },
+    )]
+    fn test_compute_integrity(algorithm: IntegrityAlgorithm, expected: &str) {
+        assert_eq!(compute_integrity(b"foo", algorithm), expected);
+    }
+
+    #[test]
+    fn test_matches_integrity() {
+        let sha256 = compute_integrity(b"foo", IntegrityAlgorithm::Sha256);
+        let sha512 = compute_integrity(b"foo", IntegrityAlgorithm::Sha512);
+        let other = compute_integrity(b"bar", IntegrityAlgorithm::Sha512);
+
+        assert!(matches_integrity(&sha256, b"foo"));
+        assert!(!matches_integrity(&sha256, b"bar"));
+        assert!(matches_integrity(&format!("{} {}?option", other, sha512), b"foo"));
+        assert!(!matches_integrity(&format!("{} {}", sha512, sha256), b"bar"));
+        assert!(!matches_integrity(&format!("{} {}", other, compute_integrity(b"foo", IntegrityAlgorithm::Sha256)), b"foo"));
+        assert!(!matches_integrity("md5-unsupported", b"foo"));
+        assert!(!matches_integrity("", b"foo"));
+    }
+
+    #[test]
+    fn test_resource_integrity() {
+        let mut icon = IconResource::default();
+        assert_matches!(icon.verify_integrity(b"anything"), Ok(()));
+
+        icon.compute_integrity(b"image", IntegrityAlgorithm::default());
+        assert!(icon.integrity.as_deref().unwrap().starts_with("sha384-"));
+        assert_matches!(icon.verify_integrity(b"image"), Ok(()));
+        assert_matches!(icon.verify_integrity(b"modified"), Err(ManifestError::IntegrityMismatch));
+
+        let mut screenshot = ScreenshotResource::default();
+        screenshot.compute_integrity(b"image", IntegrityAlgorithm::Sha256);
+        assert_matches!(screenshot.verify_integrity(b"modified"), Err(ManifestError::IntegrityMismatch));
+    }
+}
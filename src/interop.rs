@@ -0,0 +1,226 @@
+//! Contains conversions from and to the [`webmanifest`] crate types.
+//!
+//! The [`webmanifest`](https://docs.rs/webmanifest) crate provides a builder for
+//! generating simple manifests. These conversions allow projects that use it to
+//! migrate to this crate incrementally:
+//!
+//! - Manifests built with [`webmanifest::Manifest`] can be converted to [`WebAppManifest`]
+//!   with [`TryFrom`].
+//! - Manifests can be converted back with [`WebmanifestDocument`], which owns the data
+//!   borrowed by the [`webmanifest::Manifest`] type.
+//!
+//! The `webmanifest` crate only supports a subset of manifest members, so other members
+//! are dropped when converting to its types.
+
+use std::convert::{TryFrom, TryInto};
+
+use serde_json::{Map, Value};
+
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+/// The members that are supported by the [`webmanifest`] crate.
+const SUPPORTED_MEMBERS: &[&str] = &[
+    "name",
+    "short_name",
+    "start_url",
+    "display",
+    "background_color",
+    "description",
+    "dir",
+    "orientation",
+    "lang",
+    "scope",
+    "theme_color",
+    "prefer_related_applications",
+];
+
+impl TryFrom<webmanifest::Manifest<'_, '_, '_>> for WebAppManifest {
+    type Error = ManifestError;
+
+    /// Converts the manifest built with the [`webmanifest`] crate.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest contains invalid values, such as invalid colors.
+    ///
+    fn try_from(manifest: webmanifest::Manifest) -> Result<Self, Self::Error> {
+        let mut value = serde_json::to_value(manifest)?;
+
+        // The `webmanifest` crate uses a non-standard name of the fullscreen display mode
+        if let Some(display) = value.get_mut("display") {
+            if display == "full-screen" {
+                *display = Value::from("fullscreen");
+            }
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// A manifest that can be borrowed as a [`webmanifest::Manifest`].
+///
+/// The [`webmanifest::Manifest`] type borrows all its strings, so this type stores
+/// the converted manifest members.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WebmanifestDocument {
+    document: Value,
+}
+
+impl WebmanifestDocument {
+    /// Borrows the manifest as a [`webmanifest::Manifest`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be converted.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn manifest(&self) -> Result<webmanifest::Manifest<'_, '_, '_>, ManifestError> {
+        use serde::Deserialize;
+        Ok(webmanifest::Manifest::deserialize(&self.document)?)
+    }
+}
+
+impl TryFrom<&WebAppManifest> for WebmanifestDocument {
+    type Error = ManifestError;
+
+    /// Converts the manifest to members supported by the [`webmanifest`] crate.
+    ///
+    /// The name falls back to the short name, as it is required by the `webmanifest`
+    /// crate. Related applications without URLs are dropped, and icons without types
+    /// get types guessed from their file extensions.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    fn try_from(manifest: &WebAppManifest) -> Result<Self, Self::Error> {
+        let value = serde_json::to_value(manifest)?;
+        let mut document = Map::new();
+
+        for member in SUPPORTED_MEMBERS {
+            if let Some(value) = value.get(*member).filter(|value| !value.is_null()) {
+                document.insert(member.to_string(), value.clone());
+            }
+        }
+
+        let name = manifest.name.as_deref().or(manifest.short_name.as_deref()).unwrap_or_default();
+        document.insert("name".to_string(), Value::from(name));
+
+        if let Some(display) = document.get_mut("display") {
+            if display == "fullscreen" {
+                *display = Value::from("full-screen");
+            }
+        }
+
+        let icons: Vec<Value> = manifest
+            .icons
+            .iter()
+            .filter_map(|icon| {
+                let src: String = icon.src.clone().try_into().ok()?;
+                let sizes =
+                    icon.sizes.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ");
+                let r#type = match &icon.r#type {
+                    Some(r#type) => r#type.to_string(),
+                    None => guess_image_type(&src).to_string(),
+                };
+                Some(serde_json::json!({ "src": src, "sizes": sizes, "type": r#type }))
+            })
+            .collect();
+        document.insert("icons".to_string(), Value::from(icons));
+
+        let related_applications: Vec<Value> = manifest
+            .related_applications
+            .iter()
+            .filter_map(|application| {
+                let url: String = application.url.clone()?.try_into().ok()?;
+                Some(serde_json::json!({ "platform": application.platform, "url": url }))
+            })
+            .collect();
+        document.insert("related_applications".to_string(), Value::from(related_applications));
+
+        Ok(Self { document: Value::Object(document) })
+    }
+}
+
+/// Guesses the media type of the image from its file extension.
+fn guess_image_type(src: &str) -> &'static str {
+    let path = src.split(['?', '#']).next().unwrap_or_default();
+    let extension = path.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+
+    use webmanifest::{DisplayMode, Icon, Manifest, Related};
+
+    use super::*;
+    use crate::types::*;
+
+    #[test]
+    fn test_from_webmanifest() {
+        let icon = Icon::new("icons/icon-192.png", "192x192");
+        let related =
+            Related::new("play", "https://play.google.com/store/apps/details?id=com.example");
+        let builder = Manifest::builder("Example App")
+            .short_name("Example")
+            .display_mode(DisplayMode::FullScreen)
+            .theme_color("#ff0000")
+            .icon(&icon)
+            .related(&related);
+
+        let manifest: WebAppManifest = builder.try_into().unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("Example App"));
+        assert_eq!(manifest.display, Display::Fullscreen);
+        assert_eq!(manifest.theme_color, Some(csscolorparser::parse("#ff0000").unwrap()));
+        assert_eq!(manifest.icons[0].src, Url::Relative("icons/icon-192.png".to_string()));
+        assert_eq!(manifest.related_applications[0].platform, Platform::Play);
+    }
+
+    #[test]
+    fn test_to_webmanifest() {
+        let manifest: WebAppManifest = serde_json::from_str(
+            r#"{
+            "short_name": "Example",
+            "display": "fullscreen",
+            "icons": [{ "src": "/icon.svg", "sizes": "any" }],
+            "related_applications": [{ "platform": "play", "id": "com.example" }],
+            "shortcuts": [{ "name": "Open", "url": "/open" }]
+        }"#,
+        )
+        .unwrap();
+
+        let document = WebmanifestDocument::try_from(&manifest).unwrap();
+        let json = document.manifest().unwrap().build().unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&json).unwrap(),
+            serde_json::json!({
+                "name": "Example",
+                "short_name": "Example",
+                "display": "full-screen",
+                "dir": "auto",
+                "orientation": "any",
+                "prefer_related_applications": false,
+                "icons": [{ "src": "/icon.svg", "sizes": "any", "type": "image/svg+xml" }],
+                "related_applications": []
+            })
+        );
+
+        let roundtrip: WebAppManifest = document.manifest().unwrap().try_into().unwrap();
+        assert_eq!(roundtrip.display, Display::Fullscreen);
+    }
+}
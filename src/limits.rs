@@ -0,0 +1,163 @@
+//! Contains resource limits for parsing untrusted manifests.
+//!
+//! Services that parse manifests from untrusted sources should limit the resources a
+//! single manifest can consume. [`WebAppManifest::from_slice_with_limits`] rejects
+//! manifests that exceed any of the configured [`ParseLimits`] with
+//! [`ManifestError::LimitExceeded`] before they are converted to the manifest type.
+
+use serde_json::Value;
+use smart_default::SmartDefault;
+
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+/// Limits that are enforced when parsing untrusted manifests.
+///
+/// The default limits are generous enough for all real-world manifests.
+#[derive(SmartDefault, Debug, Eq, PartialEq, Clone)]
+pub struct ParseLimits {
+    /// The maximum size of the document in bytes.
+    ///
+    /// Defaults to 1 MiB.
+    #[default(1024 * 1024)]
+    pub max_document_bytes: usize,
+
+    /// The maximum number of icons in any `icons` member, including icons of shortcuts
+    /// and file handlers.
+    ///
+    /// Defaults to 64.
+    #[default(64)]
+    pub max_icons: usize,
+
+    /// The maximum number of shortcuts.
+    ///
+    /// Defaults to 32.
+    #[default(32)]
+    pub max_shortcuts: usize,
+
+    /// The maximum number of screenshots.
+    ///
+    /// Defaults to 32.
+    #[default(32)]
+    pub max_screenshots: usize,
+
+    /// The maximum length of any string or member name in bytes. This also applies to
+    /// URLs, so it should be large enough for images embedded as `data:` URLs.
+    ///
+    /// Defaults to 64 KiB.
+    #[default(64 * 1024)]
+    pub max_string_bytes: usize,
+}
+
+impl WebAppManifest {
+    /// Parses the manifest from untrusted JSON bytes while enforcing the limits.
+    ///
+    /// # Parameters
+    ///
+    /// - `bytes`: The JSON document of the manifest.
+    /// - `limits`: The limits that are enforced.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::LimitExceeded`] if the document exceeds any of the limits.
+    /// - [`ManifestError::Json`] if the document is not a valid manifest.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn from_slice_with_limits(
+        bytes: &[u8],
+        limits: &ParseLimits,
+    ) -> Result<Self, ManifestError> {
+        check_limit("max_document_bytes", bytes.len(), limits.max_document_bytes)?;
+
+        let document: Value = serde_json::from_slice(bytes)?;
+        check_value(&document, limits)?;
+
+        Ok(serde_json::from_value(document)?)
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn check_value(value: &Value, limits: &ParseLimits) -> Result<(), ManifestError> {
+    match value {
+        Value::String(string) => {
+            check_limit("max_string_bytes", string.len(), limits.max_string_bytes)?
+        }
+        Value::Array(array) => {
+            for item in array {
+                check_value(item, limits)?;
+            }
+        }
+        Value::Object(object) => {
+            for (key, item) in object {
+                check_limit("max_string_bytes", key.len(), limits.max_string_bytes)?;
+
+                if let Value::Array(array) = item {
+                    match key.as_str() {
+                        "icons" => check_limit("max_icons", array.len(), limits.max_icons)?,
+                        "shortcuts" => {
+                            check_limit("max_shortcuts", array.len(), limits.max_shortcuts)?
+                        }
+                        "screenshots" => {
+                            check_limit("max_screenshots", array.len(), limits.max_screenshots)?
+                        }
+                        _ => {}
+                    }
+                }
+
+                check_value(item, limits)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+fn check_limit(limit: &'static str, value: usize, maximum: usize) -> Result<(), ManifestError> {
+    if value > maximum {
+        return Err(ManifestError::LimitExceeded { limit, maximum });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+
+    #[allow(clippy::result_large_err)]
+    fn parse(document: Value, limits: ParseLimits) -> Result<WebAppManifest, ManifestError> {
+        WebAppManifest::from_slice_with_limits(document.to_string().as_bytes(), &limits)
+    }
+
+    #[test]
+    fn test_within_limits() {
+        let document = json!({ "name": "Example", "icons": [{ "src": "icon.png" }] });
+        assert_eq!(parse(document, ParseLimits::default()).unwrap().name.as_deref(), Some("Example"));
+    }
+
+    #[test]
+    fn test_exceeded_limits() {
+        let document = json!({ "name": "Example" });
+        let limits = ParseLimits { max_document_bytes: 8, ..Default::default() };
+        assert_matches!(parse(document, limits), Err(ManifestError::LimitExceeded { limit: "max_document_bytes", maximum: 8 }));
+
+        let document = json!({ "name": "Example" });
+        let limits = ParseLimits { max_string_bytes: 4, ..Default::default() };
+        assert_matches!(parse(document, limits), Err(ManifestError::LimitExceeded { limit: "max_string_bytes", .. }));
+
+        let document = json!({ "screenshots": [{ "src": "1.png" }, { "src": "2.png" }] });
+        let limits = ParseLimits { max_screenshots: 1, ..Default::default() };
+        assert_matches!(parse(document, limits), Err(ManifestError::LimitExceeded { limit: "max_screenshots", .. }));
+
+        let document = json!({ "shortcuts": [{ "name": "Open", "url": "/", "icons": [{ "src": "1.png" }, { "src": "2.png" }] }] });
+        let limits = ParseLimits { max_icons: 1, ..Default::default() };
+        assert_matches!(parse(document, limits), Err(ManifestError::LimitExceeded { limit: "max_icons", .. }));
+    }
+}
@@ -0,0 +1,464 @@
+//! Contains URL patterns used for pattern-based scoping.
+//!
+//! The [`UrlPattern`] type implements matching of a subset of the [URL Pattern][urlpattern]
+//! syntax, which is used by proposals that extend the application scope, such as
+//! [`scope_patterns`][crate::WebAppManifest::scope_patterns]. The following syntax is
+//! supported in each component:
+//!
+//! - Literal characters, which can be escaped with a backslash.
+//! - Wildcards (`*`), which match any characters.
+//! - Named groups (`:name`), which match one path segment in the pathname, one label
+//!   in the hostname, and any non-empty text in other components. Named groups can be
+//!   made optional with the `?` modifier, which also makes the preceding `/` optional
+//!   in the pathname.
+//!
+//! Regular expression groups and grouping with braces are not supported.
+//!
+//! [urlpattern]: https://urlpattern.spec.whatwg.org/
+
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::errors::ManifestError;
+use crate::types::*;
+
+/// The components of the URL pattern as specified in the manifest.
+///
+/// Components that are not specified are inherited from the base URL or match any
+/// value, as described in [`UrlPatternInit::resolve`].
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Default, Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct UrlPatternInit {
+    /// The pattern of the URL scheme, without the trailing colon.
+    pub protocol: Option<String>,
+
+    /// The pattern of the URL username.
+    pub username: Option<String>,
+
+    /// The pattern of the URL password.
+    pub password: Option<String>,
+
+    /// The pattern of the URL host name.
+    pub hostname: Option<String>,
+
+    /// The pattern of the URL port. The empty string matches the default port.
+    pub port: Option<String>,
+
+    /// The pattern of the URL path.
+    pub pathname: Option<String>,
+
+    /// The pattern of the URL query, without the leading question mark.
+    pub search: Option<String>,
+
+    /// The pattern of the URL fragment, without the leading hash.
+    pub hash: Option<String>,
+
+    /// The base URL against which the pattern is resolved.
+    #[serde(rename = "baseURL")]
+    pub base_url: Option<String>,
+}
+
+impl UrlPatternInit {
+    /// Resolves the pattern against the base URL.
+    ///
+    /// The base URL of the pattern takes precedence over the provided base URL.
+    /// Components that precede the first specified component are inherited from the
+    /// base URL, and the remaining unspecified components match any value. Relative
+    /// pathnames are resolved against the directory of the base URL path.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::UrlParsing`] if the base URL of the pattern is not valid.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn resolve(&self, base_url: &AbsoluteUrl) -> Result<UrlPatternInit, ManifestError> {
+        let base_url = match &self.base_url {
+            Some(pattern_base_url) => base_url.join(pattern_base_url)?,
+            None => base_url.clone(),
+        };
+
+        let components = [
+            &self.protocol,
+            &self.username,
+            &self.password,
+            &self.hostname,
+            &self.port,
+            &self.pathname,
+            &self.search,
+            &self.hash,
+        ];
+        let first_specified = components.iter().position(|component| component.is_some());
+        let inherits = |index: usize| first_specified.is_none_or(|first| index < first);
+
+        let port = base_url.port().map(|port| port.to_string()).unwrap_or_default();
+        let base_components = [
+            base_url.scheme(),
+            base_url.username(),
+            base_url.password().unwrap_or_default(),
+            base_url.host_str().unwrap_or_default(),
+            &port,
+            base_url.path(),
+            base_url.query().unwrap_or_default(),
+            base_url.fragment().unwrap_or_default(),
+        ];
+
+        let mut resolved =
+            components.iter().enumerate().map(|(index, component)| match component {
+                Some(component) => component.clone(),
+                None if inherits(index) => escape_pattern(base_components[index]),
+                None => "*".to_string(),
+            });
+
+        let mut next = || resolved.next().unwrap_or_default();
+        let (protocol, username, password, hostname, port) =
+            (next(), next(), next(), next(), next());
+        let (mut pathname, search, hash) = (next(), next(), next());
+
+        // Relative pathnames are resolved against the directory of the base path
+        if !pathname.starts_with('/')
+            && !pathname.starts_with('*')
+            && base_url.path().starts_with('/')
+        {
+            let directory = &base_url.path()[..=base_url.path().rfind('/').unwrap_or(0)];
+            pathname = format!("{}{}", escape_pattern(directory), pathname);
+        }
+
+        Ok(UrlPatternInit {
+            protocol: Some(protocol),
+            username: Some(username),
+            password: Some(password),
+            hostname: Some(hostname),
+            port: Some(port),
+            pathname: Some(pathname),
+            search: Some(search),
+            hash: Some(hash),
+            base_url: None,
+        })
+    }
+}
+
+/// A compiled URL pattern that can be matched against URLs.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct UrlPattern {
+    components: [Vec<Token>; 8],
+}
+
+/// A token of the compiled component pattern.
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Token {
+    /// Matches the literal text.
+    Literal(String),
+
+    /// Matches any text, including the empty text.
+    Wildcard,
+
+    /// Matches non-empty text without the separator, optionally preceded by the prefix.
+    Group { prefix: Option<char>, separator: Option<char>, optional: bool },
+}
+
+impl UrlPattern {
+    /// Compiles the URL pattern.
+    ///
+    /// The pattern should be [resolved][UrlPatternInit::resolve] first, otherwise
+    /// unspecified components match any value.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::InvalidUrlPattern`] if any of the components uses unsupported syntax.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn new(init: &UrlPatternInit) -> Result<Self, ManifestError> {
+        let compile = |component: &Option<String>, separator: Option<char>, lowercase: bool| {
+            let pattern = component.as_deref().unwrap_or("*");
+            let pattern =
+                if lowercase { pattern.to_ascii_lowercase() } else { pattern.to_string() };
+            let pattern =
+                if separator == Some('.') { to_ascii_hostname(&pattern) } else { pattern };
+            compile_component(&pattern, separator)
+        };
+
+        Ok(Self {
+            components: [
+                compile(&init.protocol, None, true)?,
+                compile(&init.username, None, false)?,
+                compile(&init.password, None, false)?,
+                compile(&init.hostname, Some('.'), true)?,
+                compile(&init.port, None, false)?,
+                compile(&init.pathname, Some('/'), false)?,
+                compile(&init.search, None, false)?,
+                compile(&init.hash, None, false)?,
+            ],
+        })
+    }
+
+    /// Checks whether the URL matches the pattern.
+    ///
+    /// The default port of the URL scheme is equivalent to the empty port, so the URL
+    /// `https://example.com/` matches both the empty port and the port `443`.
+    pub fn test(&self, url: &AbsoluteUrl) -> bool {
+        let port = url.port().map(|port| port.to_string()).unwrap_or_default();
+        let default_port = match url.port() {
+            None => url.port_or_known_default().map(|port| port.to_string()),
+            Some(_) => None,
+        };
+
+        let port_matches = match_tokens(&self.components[4], &port)
+            || default_port.is_some_and(|port| match_tokens(&self.components[4], &port));
+
+        let components = [
+            url.scheme(),
+            url.username(),
+            url.password().unwrap_or_default(),
+            url.host_str().unwrap_or_default(),
+            &port,
+            url.path(),
+            url.query().unwrap_or_default(),
+            url.fragment().unwrap_or_default(),
+        ];
+
+        port_matches
+            && self
+                .components
+                .iter()
+                .zip(components.iter())
+                .enumerate()
+                .all(|(index, (tokens, input))| index == 4 || match_tokens(tokens, input))
+    }
+}
+
+/// Converts the literal Unicode labels of the hostname pattern to punycode, so they match
+/// the ASCII hosts of parsed URLs. Labels that contain pattern syntax are not converted.
+fn to_ascii_hostname(pattern: &str) -> String {
+    let labels: Vec<_> = pattern
+        .split('.')
+        .map(|label| {
+            if label.is_ascii() || label.contains(['*', ':', '\\', '?']) {
+                label.to_string()
+            } else {
+                crate::to_ascii_host(label)
+            }
+        })
+        .collect();
+
+    labels.join(".")
+}
+
+/// Escapes the special characters of the pattern syntax.
+fn escape_pattern(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for char in input.chars() {
+        if matches!(char, '*' | ':' | '?' | '+' | '(' | ')' | '{' | '}' | '\\') {
+            output.push('\\');
+        }
+        output.push(char);
+    }
+
+    output
+}
+
+#[allow(clippy::result_large_err)]
+fn compile_component(pattern: &str, separator: Option<char>) -> Result<Vec<Token>, ManifestError> {
+    let invalid = || ManifestError::InvalidUrlPattern { pattern: pattern.to_string() };
+
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => literal.push(chars.next().ok_or_else(invalid)?),
+            '*' => {
+                push_literal(&mut tokens, &mut literal);
+                tokens.push(Token::Wildcard);
+            }
+            ':' => {
+                let mut name = String::new();
+                while let Some(char) =
+                    chars.peek().filter(|char| char.is_alphanumeric() || **char == '_')
+                {
+                    name.push(*char);
+                    chars.next();
+                }
+
+                if name.is_empty() {
+                    return Err(invalid());
+                }
+
+                let optional = chars.next_if_eq(&'?').is_some();
+
+                // The separator preceding the group in the pathname is part of the group
+                let prefix = if separator == Some('/') && literal.ends_with('/') {
+                    literal.pop();
+                    Some('/')
+                } else {
+                    None
+                };
+
+                push_literal(&mut tokens, &mut literal);
+                tokens.push(Token::Group { prefix, separator, optional });
+            }
+            '(' | ')' | '{' | '}' | '+' | '?' => return Err(invalid()),
+            char => literal.push(char),
+        }
+    }
+
+    push_literal(&mut tokens, &mut literal);
+    Ok(tokens)
+}
+
+fn push_literal(tokens: &mut Vec<Token>, literal: &mut String) {
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(std::mem::take(literal)));
+    }
+}
+
+/// Matches the input against the tokens.
+///
+/// The positions in the input that can be reached after each token are computed one
+/// token at a time, instead of backtracking, so matching takes polynomial time regardless
+/// of the number of wildcards and groups in untrusted patterns.
+fn match_tokens(tokens: &[Token], input: &str) -> bool {
+    let mut reachable = vec![false; input.len() + 1];
+    reachable[0] = true;
+
+    for token in tokens {
+        let mut next = vec![false; input.len() + 1];
+        let starts = (0..=input.len()).filter(|start| reachable[*start]);
+
+        match token {
+            Token::Literal(literal) => {
+                for start in starts {
+                    if input[start..].starts_with(literal.as_str()) {
+                        next[start + literal.len()] = true;
+                    }
+                }
+            }
+            Token::Wildcard => {
+                // Every position after the first reachable one can be reached
+                if let Some(first) = starts.min() {
+                    for (index, _) in input[first..].char_indices() {
+                        next[first + index] = true;
+                    }
+                    next[input.len()] = true;
+                }
+            }
+            Token::Group { prefix, separator, optional } => {
+                for start in starts {
+                    if *optional {
+                        next[start] = true;
+                    }
+
+                    let (start, rest) = match prefix {
+                        Some(prefix) => match input[start..].strip_prefix(*prefix) {
+                            Some(rest) => (start + prefix.len_utf8(), rest),
+                            None => continue,
+                        },
+                        None => (start, &input[start..]),
+                    };
+
+                    let end = separator.and_then(|separator| rest.find(separator));
+                    let end = end.unwrap_or(rest.len());
+
+                    let ends = rest[..end].char_indices().skip(1).map(|(index, _)| index);
+                    for index in ends.chain(Some(end).filter(|end| *end > 0)) {
+                        next[start + index] = true;
+                    }
+                }
+            }
+        }
+
+        reachable = next;
+    }
+
+    reachable[input.len()]
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+    use parameterized::parameterized;
+
+    use super::*;
+
+    fn pattern(pathname: &str) -> UrlPattern {
+        let base = AbsoluteUrl::parse("https://example.com/app/manifest.json").unwrap();
+        let init = UrlPatternInit { pathname: Some(pathname.to_string()), ..Default::default() };
+        UrlPattern::new(&init.resolve(&base).unwrap()).unwrap()
+    }
+
+    #[parameterized(
+        pathname = {
+            "/docs/*", "/docs/*", "/books/:id", "/books/:id", "/books/:id?", "/books/:id?",
+            "help", "/a\\*b", "*.pdf",
+        },
+        url = {
+            "https://example.com/docs/", "https://other.com/docs/a", "https://example.com/books/12",
+            "https://example.com/books/12/pages", "https://example.com/books", "https://example.com/books/",
+            "https://example.com/app/help?q=1", "https://example.com/a*b", "https://example.com/files/a.pdf",
+        },
+        expected = { true, false, true, false, true, false, true, true, true },
+    )]
+    fn test_pattern_matching(pathname: &str, url: &str, expected: bool) {
+        assert_eq!(pattern(pathname).test(&AbsoluteUrl::parse(url).unwrap()), expected);
+    }
+
+    #[test]
+    fn test_pattern_matching_many_wildcards() {
+        let url = AbsoluteUrl::parse(&format!("https://example.com/{}", "a".repeat(40))).unwrap();
+        assert!(!pattern(&format!("/{}b", "*a".repeat(12))).test(&url));
+        assert!(pattern(&format!("/{}", "*a".repeat(12))).test(&url));
+        assert!(!pattern(&format!("/{}b", ":x?a".repeat(12))).test(&url));
+        assert!(pattern("/:x?aa*:y").test(&url));
+    }
+
+    #[test]
+    fn test_resolve_pattern() {
+        let base = AbsoluteUrl::parse("https://example.com:8080/app/manifest.json?v=1").unwrap();
+
+        let init = UrlPatternInit { search: Some("q=*".to_string()), ..Default::default() };
+        let resolved = init.resolve(&base).unwrap();
+        assert_eq!(resolved.hostname.as_deref(), Some("example.com"));
+        assert_eq!(resolved.port.as_deref(), Some("8080"));
+        assert_eq!(resolved.pathname.as_deref(), Some("/app/manifest.json"));
+        assert_eq!(resolved.search.as_deref(), Some("q=*"));
+        assert_eq!(resolved.hash.as_deref(), Some("*"));
+
+        let init = UrlPatternInit {
+            hostname: Some(":tenant.example.com".to_string()),
+            ..Default::default()
+        };
+        let pattern = UrlPattern::new(&init.resolve(&base).unwrap()).unwrap();
+        assert!(pattern.test(&AbsoluteUrl::parse("https://acme.example.com/any/path").unwrap()));
+        assert!(!pattern.test(&AbsoluteUrl::parse("https://a.b.example.com/").unwrap()));
+        assert!(!pattern.test(&AbsoluteUrl::parse("http://acme.example.com/").unwrap()));
+
+        let init = UrlPatternInit { port: Some("443".to_string()), ..Default::default() };
+        let pattern = UrlPattern::new(&init.resolve(&base).unwrap()).unwrap();
+        assert!(pattern.test(&AbsoluteUrl::parse("https://example.com/").unwrap()));
+        assert!(pattern.test(&AbsoluteUrl::parse("https://example.com:443/").unwrap()));
+        assert!(!pattern.test(&AbsoluteUrl::parse("http://example.com/").unwrap()));
+
+        let init = UrlPatternInit {
+            hostname: Some("*.Bücher.example".to_string()),
+            ..Default::default()
+        };
+        let pattern = UrlPattern::new(&init.resolve(&base).unwrap()).unwrap();
+        assert!(pattern.test(&AbsoluteUrl::parse("https://shop.bücher.example/").unwrap()));
+        assert!(pattern.test(&AbsoluteUrl::parse("https://shop.xn--bcher-kva.example/").unwrap()));
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let init =
+            UrlPatternInit { pathname: Some("/books/(\\d+)".to_string()), ..Default::default() };
+        assert_matches!(UrlPattern::new(&init), Err(ManifestError::InvalidUrlPattern { .. }));
+
+        let init = UrlPatternInit { pathname: Some("/books/:".to_string()), ..Default::default() };
+        assert_matches!(UrlPattern::new(&init), Err(ManifestError::InvalidUrlPattern { .. }));
+    }
+}
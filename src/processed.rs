@@ -0,0 +1,257 @@
+//! Contains the processed representation of manifests.
+//!
+//! [`WebAppManifest`] stores its URLs as [`Url`], which can be relative or unknown until
+//! the manifest is [processed][WebAppManifest::process]. Code that requires absolute URLs
+//! therefore has to handle the other variants, even if it only ever receives processed
+//! manifests. [`ProcessedManifest`] can only be constructed by processing a manifest, so
+//! its start URL, scope and identity accessors return [`ProcessedUrl`] values directly and
+//! cannot fail.
+
+use std::convert::TryInto;
+use std::ops::Deref;
+
+use serde::{Serialize, Serializer};
+
+use crate::errors::ManifestError;
+use crate::types::*;
+use crate::{ProcessOptions, WebAppManifest};
+
+/// The manifest as it was deserialized, with URLs that may be relative or unknown.
+pub type RawManifest = WebAppManifest;
+
+/// The URL of a processed manifest, which is always absolute.
+pub type ProcessedUrl = AbsoluteUrl;
+
+/// A manifest that has been successfully processed.
+///
+/// The start URL, scope and identity of the processed manifest are available as
+/// [`ProcessedUrl`] values, the start URL is in the same origin as the document URL and
+/// it is within the scope. Other URLs, such as the sources of icons and screenshots, the
+/// shortcut URLs, the protocol and file handler actions and the share target action, are
+/// only available as [`Url`] values of the underlying manifest. The manifest members can
+/// be read through [`Deref`], but they cannot be modified, as that could break these
+/// guarantees. Use [`into_inner`][ProcessedManifest::into_inner] to
+/// get back the underlying manifest.
+///
+/// The raw manifest from which the processed manifest was created is preserved, so the
+/// original URLs, such as relative paths written by the manifest author, remain available
+/// through [`raw`][ProcessedManifest::raw].
+///
+/// The processed manifest serializes the same as the underlying manifest.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProcessedManifest {
+    raw: RawManifest,
+    manifest: RawManifest,
+    start_url: ProcessedUrl,
+    scope: ProcessedUrl,
+    id: ProcessedUrl,
+}
+
+impl ProcessedManifest {
+    /// Processes the raw manifest.
+    ///
+    /// # Parameters
+    ///
+    /// - `manifest`: The raw manifest.
+    /// - `document_url`: The main document URL from which the manifest parsing was triggered.
+    /// - `manifest_url`: The manifest URL.
+    /// - `options`: The options that control processing.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The processed manifest.
+    /// - `Err`: An error in case of error while processing the manifest.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError`][ManifestError] if the error occurs while processing the manifest.
+    ///
+    /// # See also
+    ///
+    /// - [`WebAppManifest::process_with_options`]
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn new(
+        raw: RawManifest,
+        document_url: &AbsoluteUrl,
+        manifest_url: &AbsoluteUrl,
+        options: &ProcessOptions,
+    ) -> Result<Self, ManifestError> {
+        let mut manifest = raw.clone();
+        manifest.process_with_options(document_url, manifest_url, options)?;
+
+        let start_url = manifest.start_url.clone().try_into()?;
+        let scope = manifest.scope.clone().try_into()?;
+        let id = manifest.id.clone().unwrap_or_default().try_into()?;
+
+        Ok(Self { raw, manifest, start_url, scope, id })
+    }
+
+    /// Returns the processed start URL.
+    pub fn start_url(&self) -> &ProcessedUrl {
+        &self.start_url
+    }
+
+    /// Returns the processed scope URL.
+    pub fn scope(&self) -> &ProcessedUrl {
+        &self.scope
+    }
+
+    /// Returns the processed identity of the application.
+    pub fn id(&self) -> &ProcessedUrl {
+        &self.id
+    }
+
+    /// Returns the raw manifest as it was before processing.
+    ///
+    /// It can be used to re-emit the manifest unchanged, while the processed manifest
+    /// provides the resolved URLs.
+    pub fn raw(&self) -> &RawManifest {
+        &self.raw
+    }
+
+    /// Returns the underlying manifest.
+    pub fn into_inner(self) -> RawManifest {
+        self.manifest
+    }
+}
+
+impl Deref for ProcessedManifest {
+    type Target = RawManifest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.manifest
+    }
+}
+
+impl AsRef<RawManifest> for ProcessedManifest {
+    fn as_ref(&self) -> &RawManifest {
+        &self.manifest
+    }
+}
+
+impl From<ProcessedManifest> for RawManifest {
+    fn from(processed: ProcessedManifest) -> Self {
+        processed.manifest
+    }
+}
+
+impl Serialize for ProcessedManifest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.manifest.serialize(serializer)
+    }
+}
+
+impl WebAppManifest {
+    /// Processes a copy of the manifest with the default options and returns it as
+    /// a [`ProcessedManifest`], leaving the current manifest unchanged.
+    ///
+    /// This is useful when the raw manifest must be retained, for example, to serve
+    /// it unchanged. See [`process`][WebAppManifest::process] for details about processing.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError`][ManifestError] if the error occurs while processing the manifest.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn processed(
+        &self,
+        document_url: &AbsoluteUrl,
+        manifest_url: &AbsoluteUrl,
+    ) -> Result<ProcessedManifest, ManifestError> {
+        ProcessedManifest::new(self.clone(), document_url, manifest_url, &ProcessOptions::default())
+    }
+
+    /// Processes the manifest and converts it into a [`ProcessedManifest`].
+    ///
+    /// See [`ProcessedManifest::new`] for details.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError`][ManifestError] if the error occurs while processing the manifest.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn into_processed(
+        self,
+        document_url: &AbsoluteUrl,
+        manifest_url: &AbsoluteUrl,
+        options: &ProcessOptions,
+    ) -> Result<ProcessedManifest, ManifestError> {
+        ProcessedManifest::new(self, document_url, manifest_url, options)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_processed_manifest() {
+        let document_url = AbsoluteUrl::parse("https://example.com/app/index.html").unwrap();
+        let manifest_url = AbsoluteUrl::parse("https://example.com/app/manifest.json").unwrap();
+
+        let manifest = WebAppManifest {
+            start_url: Url::Relative("start.html".to_string()),
+            name: Some("Example".to_string()),
+            ..Default::default()
+        };
+
+        let processed = manifest
+            .clone()
+            .into_processed(&document_url, &manifest_url, &ProcessOptions::default())
+            .unwrap();
+
+        assert_eq!(processed.start_url().as_str(), "https://example.com/app/start.html");
+        assert_eq!(processed.scope().as_str(), "https://example.com/app/");
+        assert_eq!(processed.id().as_str(), "https://example.com/app/start.html");
+        assert_eq!(processed.name.as_deref(), Some("Example"));
+
+        assert_eq!(processed.raw(), &manifest);
+        assert_eq!(processed.raw().start_url, Url::Relative("start.html".to_string()));
+
+        let serialized = serde_json::to_value(&processed).unwrap();
+        assert_eq!(serialized, serde_json::to_value(processed.as_ref()).unwrap());
+
+        let inner = processed.into_inner();
+        assert_eq!(inner.start_url, Url::Absolute(document_url.join("start.html").unwrap()));
+        assert_ne!(inner, manifest);
+    }
+
+    #[test]
+    fn test_processed_leaves_original() {
+        let document_url = AbsoluteUrl::parse("https://example.com/index.html").unwrap();
+        let manifest_url = AbsoluteUrl::parse("https://example.com/manifest.json").unwrap();
+
+        let manifest =
+            WebAppManifest { start_url: Url::Relative("/start".to_string()), ..Default::default() };
+        let original = manifest.clone();
+
+        let processed = manifest.processed(&document_url, &manifest_url).unwrap();
+        assert_eq!(manifest, original);
+        assert_eq!(processed.raw(), &original);
+        assert_eq!(processed.start_url().as_str(), "https://example.com/start");
+    }
+
+    #[test]
+    fn test_processed_manifest_invalid() {
+        let document_url = AbsoluteUrl::parse("https://example.com/index.html").unwrap();
+        let manifest_url = AbsoluteUrl::parse("https://example.com/manifest.json").unwrap();
+
+        let manifest = WebAppManifest {
+            start_url: Url::Relative("https://example.org/".to_string()),
+            ..Default::default()
+        };
+
+        let result = ProcessedManifest::new(
+            manifest,
+            &document_url,
+            &manifest_url,
+            &ProcessOptions::default(),
+        );
+        assert_matches!(result, Err(ManifestError::NotSameOrigin { .. }));
+    }
+}
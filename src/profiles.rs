@@ -0,0 +1,197 @@
+//! Contains environment overlay profiles of manifests.
+//!
+//! Teams often maintain slightly different manifests for each environment, such as
+//! development, staging and production, which differ in start URLs, names or colors.
+//! [`ManifestProfiles`] stores the base manifest and named partial manifests that are
+//! merged over it as [JSON Merge Patches](https://www.rfc-editor.org/rfc/rfc7396).
+//!
+//! Profiles can also be stored in a single JSON document:
+//!
+//! ```json
+//! {
+//!     "base": { "name": "Example", "start_url": "https://example.com/" },
+//!     "profiles": {
+//!         "dev": { "name": "Example (Dev)", "start_url": "http://localhost:8080/" }
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+/// The base manifest with named overlay profiles.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ManifestProfiles {
+    /// The base manifest that is shared by all profiles.
+    pub base: Value,
+
+    /// The partial manifests of profiles, indexed by profile names.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Value>,
+}
+
+impl ManifestProfiles {
+    /// Creates profiles with the base manifest and without any overlays.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn new(base: &WebAppManifest) -> Result<Self, ManifestError> {
+        Ok(Self { base: serde_json::to_value(base)?, profiles: BTreeMap::new() })
+    }
+
+    /// Adds the profile with a partial manifest that is merged over the base manifest.
+    ///
+    /// An existing profile with the same name is replaced.
+    ///
+    pub fn with_profile(mut self, name: impl Into<String>, overlay: Value) -> Self {
+        self.profiles.insert(name.into(), overlay);
+        self
+    }
+
+    /// Returns the names of all profiles in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Resolves the manifest of the profile.
+    ///
+    /// See [`ManifestProfiles::resolve_layers`] for more details.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::UnknownProfile`] if the profile does not exist.
+    /// - [`ManifestError::Json`] if the resolved manifest is not valid.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn resolve(&self, name: &str) -> Result<WebAppManifest, ManifestError> {
+        self.resolve_layers(&[name])
+    }
+
+    /// Resolves the manifest of multiple profiles that are layered over each other.
+    ///
+    /// Overlays are merged over the base manifest in the provided order, so later
+    /// profiles take precedence over earlier ones, and all profiles take precedence
+    /// over the base manifest. Merging follows JSON Merge Patch:
+    ///
+    /// - Objects are merged recursively.
+    /// - Arrays and other values replace the previous value.
+    /// - `null` removes the member from the manifest.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::UnknownProfile`] if any of the profiles does not exist.
+    /// - [`ManifestError::Json`] if the resolved manifest is not valid.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn resolve_layers(&self, names: &[&str]) -> Result<WebAppManifest, ManifestError> {
+        let mut manifest = self.base.clone();
+
+        for name in names {
+            let overlay = self
+                .profiles
+                .get(*name)
+                .ok_or_else(|| ManifestError::UnknownProfile { name: name.to_string() })?;
+            merge_patch(&mut manifest, overlay);
+        }
+
+        Ok(serde_json::from_value(manifest)?)
+    }
+
+    /// Resolves the base manifest without any profiles.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the base manifest is not valid.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn resolve_base(&self) -> Result<WebAppManifest, ManifestError> {
+        self.resolve_layers(&[])
+    }
+}
+
+/// Applies the JSON Merge Patch to the target value.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let patch = match patch {
+        Value::Object(patch) => patch,
+        patch => {
+            *target = patch.clone();
+            return;
+        }
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.as_str()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target = json!({ "a": "b", "c": { "d": "e", "f": "g" }, "h": [1, 2] });
+        merge_patch(&mut target, &json!({ "a": "z", "c": { "f": null }, "h": [3] }));
+        assert_eq!(target, json!({ "a": "z", "c": { "d": "e" }, "h": [3] }));
+    }
+
+    #[test]
+    fn test_resolve_profiles() {
+        let profiles: ManifestProfiles = serde_json::from_value(json!({
+            "base": { "name": "Example", "short_name": "Ex", "start_url": "https://example.com/" },
+            "profiles": {
+                "dev": { "name": "Example (Dev)", "start_url": "http://localhost:8080/" },
+                "local": { "short_name": null }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(profiles.names().collect::<Vec<_>>(), ["dev", "local"]);
+        assert_eq!(profiles.resolve_base().unwrap().name.as_deref(), Some("Example"));
+
+        let manifest = profiles.resolve("dev").unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("Example (Dev)"));
+        assert_eq!(manifest.short_name.as_deref(), Some("Ex"));
+        assert_eq!(manifest.start_url, "http://localhost:8080/".parse().unwrap());
+
+        let manifest = profiles.resolve_layers(&["dev", "local"]).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("Example (Dev)"));
+        assert_eq!(manifest.short_name, None);
+
+        assert_matches!(profiles.resolve("prod"), Err(ManifestError::UnknownProfile { name }) if name == "prod");
+    }
+
+    #[test]
+    fn test_profiles_from_manifest() {
+        let base = WebAppManifest { name: Some("Example".to_string()), ..Default::default() };
+        let profiles = ManifestProfiles::new(&base)
+            .unwrap()
+            .with_profile("staging", json!({ "name": "Staging" }));
+
+        assert_eq!(profiles.resolve_base().unwrap(), base);
+        assert_eq!(profiles.resolve("staging").unwrap().name.as_deref(), Some("Staging"));
+    }
+}
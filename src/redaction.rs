@@ -0,0 +1,318 @@
+//! Contains redaction of manifests before they are persisted.
+//!
+//! Services that sync or store manifests of user-installed applications usually do not
+//! need all manifest members. [`WebAppManifest::redact`] and [`redact_document`] strip
+//! or anonymize members according to a [`RedactionPolicy`], so less data about the
+//! user's applications is stored.
+
+use serde_json::Value;
+use smart_default::SmartDefault;
+
+use crate::types::*;
+use crate::WebAppManifest;
+
+/// The members that are represented by the manifest type.
+///
+/// Other members of the raw manifest are considered vendor members.
+pub const KNOWN_MEMBERS: &[&str] = &[
+    "id",
+    "start_url",
+    "scope",
+    "scope_patterns",
+    "scope_extensions",
+    "name",
+    "short_name",
+    "description",
+    "categories",
+    "keywords",
+    "dir",
+    "lang",
+    "translations",
+    "display",
+    "display_override",
+    "tab_strip",
+    "orientation",
+    "background_color",
+    "theme_color",
+    "user_preferences",
+    "iarc_rating_id",
+    "prefer_related_applications",
+    "related_applications",
+    "protocol_handlers",
+    "file_handlers",
+    "launch_handler",
+    "permissions_policy",
+    "shortcuts",
+    "share_target",
+    "note_taking",
+    "icons",
+    "screenshots",
+    "widgets",
+    "serviceworker",
+    "url_handlers",
+    "capture_links",
+    "gcm_sender_id",
+    "gcm_user_visible_only",
+];
+
+/// A policy that defines which members are redacted.
+///
+/// The default policy removes members that are not needed to install and launch
+/// the application.
+#[derive(SmartDefault, Debug, Eq, PartialEq, Clone)]
+pub struct RedactionPolicy {
+    /// Whether the fingerprints of related applications should be removed.
+    ///
+    /// Enabled by default.
+    #[default(true)]
+    pub remove_fingerprints: bool,
+
+    /// Whether the screenshots should be removed.
+    ///
+    /// Enabled by default.
+    #[default(true)]
+    pub remove_screenshots: bool,
+
+    /// Whether the related applications should be removed completely.
+    ///
+    /// Disabled by default.
+    pub remove_related_applications: bool,
+
+    /// Whether the query and fragment of the start URL and the identity should be
+    /// removed. They often contain campaign parameters or tokens that identify the
+    /// user, and the processed identity is derived from the start URL.
+    ///
+    /// Enabled by default.
+    #[default(true)]
+    pub anonymize_start_url: bool,
+
+    /// Whether the push messaging members (`gcm_sender_id` and `gcm_user_visible_only`)
+    /// should be removed. The sender ID identifies the messaging project of the application.
+    ///
+    /// Enabled by default.
+    #[default(true)]
+    pub remove_push_members: bool,
+
+    /// Whether the members that are not in [`KNOWN_MEMBERS`] should be removed.
+    /// This only applies to raw documents redacted with [`redact_document`].
+    ///
+    /// Enabled by default.
+    #[default(true)]
+    pub remove_vendor_members: bool,
+}
+
+impl WebAppManifest {
+    /// Redacts the manifest according to the policy.
+    ///
+    /// # Parameters
+    ///
+    /// - `policy`: The policy that defines which members are redacted.
+    ///
+    pub fn redact(&mut self, policy: &RedactionPolicy) {
+        if policy.remove_related_applications {
+            self.related_applications.clear();
+            self.prefer_related_applications = false;
+        } else if policy.remove_fingerprints {
+            for application in &mut self.related_applications {
+                application.fingerprints.clear();
+            }
+        }
+
+        if policy.remove_screenshots {
+            self.screenshots.clear();
+        }
+
+        if policy.remove_push_members {
+            self.gcm_sender_id = None;
+            self.gcm_user_visible_only = None;
+        }
+
+        if policy.anonymize_start_url {
+            anonymize_url(&mut self.start_url);
+            if let Some(id) = &mut self.id {
+                anonymize_url(id);
+            }
+        }
+    }
+}
+
+/// Removes the query and fragment of the URL.
+fn anonymize_url(url: &mut Url) {
+    match url {
+        Url::Absolute(url) => {
+            url.set_query(None);
+            url.set_fragment(None);
+        }
+        Url::Relative(url) => {
+            if let Some(index) = url.find(['?', '#']) {
+                url.truncate(index);
+            }
+        }
+        Url::Unknown => {}
+    }
+}
+
+/// Redacts the raw manifest document according to the policy.
+///
+/// Unlike [`WebAppManifest::redact`], this can also remove vendor members that are
+/// not represented by the manifest type.
+///
+/// # Parameters
+///
+/// - `document`: The raw manifest document.
+/// - `policy`: The policy that defines which members are redacted.
+///
+pub fn redact_document(document: &mut Value, policy: &RedactionPolicy) {
+    let Some(members) = document.as_object_mut() else {
+        return;
+    };
+
+    if policy.remove_vendor_members {
+        members.retain(|member, _| KNOWN_MEMBERS.contains(&member.as_str()));
+    }
+
+    if policy.remove_related_applications {
+        members.remove("related_applications");
+        members.remove("prefer_related_applications");
+    } else if policy.remove_fingerprints {
+        if let Some(Value::Array(applications)) = members.get_mut("related_applications") {
+            for application in applications.iter_mut().filter_map(Value::as_object_mut) {
+                application.remove("fingerprints");
+            }
+        }
+    }
+
+    if policy.remove_screenshots {
+        members.remove("screenshots");
+    }
+
+    if policy.remove_push_members {
+        members.remove("gcm_sender_id");
+        members.remove("gcm_user_visible_only");
+    }
+
+    if policy.anonymize_start_url {
+        for member in &["start_url", "id"] {
+            if let Some(Value::String(url)) = members.get_mut(*member) {
+                if let Some(index) = url.find(['?', '#']) {
+                    url.truncate(index);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn document() -> Value {
+        json!({
+            "name": "Example",
+            "start_url": "https://example.com/?utm_source=homescreen#user",
+            "related_applications": [{
+                "platform": "play",
+                "id": "com.example.app",
+                "fingerprints": [{ "type": "sha256_cert", "value": "92:5A:39" }]
+            }],
+            "screenshots": [{ "src": "screenshot.png" }],
+            "gcm_sender_id": "103953800507",
+            "edge_side_panel": {}
+        })
+    }
+
+    #[test]
+    fn test_known_members() {
+        let manifest = WebAppManifest {
+            start_url: Url::Relative("/".to_string()),
+            scope: Url::Relative("/".to_string()),
+            id: Some(Url::Relative("/".to_string())),
+            name: Some("Example".to_string()),
+            short_name: Some("Example".to_string()),
+            description: Some("Example".to_string()),
+            lang: Some("en".parse().unwrap()),
+            background_color: Some(csscolorparser::parse("red").unwrap()),
+            theme_color: Some(csscolorparser::parse("red").unwrap()),
+            iarc_rating_id: Some("Example".to_string()),
+            launch_handler: Some(Default::default()),
+            share_target: Some(Default::default()),
+            note_taking: Some(Default::default()),
+            tab_strip: Some(Default::default()),
+            user_preferences: Some(Default::default()),
+            serviceworker: Some(Default::default()),
+            url_handlers: vec![Default::default()],
+            capture_links: vec![CaptureLinks::None],
+            gcm_sender_id: Some("Example".to_string()),
+            gcm_user_visible_only: Some(true),
+            ..Default::default()
+        };
+
+        let value = serde_json::to_value(manifest).unwrap();
+        let mut members: Vec<_> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        let mut known = KNOWN_MEMBERS.to_vec();
+        members.sort_unstable();
+        known.sort_unstable();
+        assert_eq!(members, known);
+    }
+
+    #[test]
+    fn test_redact_manifest() {
+        let mut manifest: WebAppManifest = serde_json::from_value(document()).unwrap();
+        manifest.redact(&RedactionPolicy::default());
+
+        assert_eq!(manifest.start_url, Url::Absolute(AbsoluteUrl::parse("https://example.com/").unwrap()));
+        assert_eq!(manifest.related_applications[0].id.as_deref(), Some("com.example.app"));
+        assert!(manifest.related_applications[0].fingerprints.is_empty());
+        assert!(manifest.screenshots.is_empty());
+        assert_eq!(manifest.gcm_sender_id, None);
+
+        manifest
+            .redact(&RedactionPolicy { remove_related_applications: true, ..Default::default() });
+        assert!(manifest.related_applications.is_empty());
+    }
+
+    #[test]
+    fn test_redact_processed_manifest() {
+        let document_url = AbsoluteUrl::parse("https://example.com/").unwrap();
+        let manifest_url = AbsoluteUrl::parse("https://example.com/manifest.json").unwrap();
+
+        let mut manifest = WebAppManifest {
+            start_url: Url::Relative("/app?token=secret#user".to_string()),
+            ..Default::default()
+        };
+        manifest.process(&document_url, &manifest_url).unwrap();
+        manifest.redact(&RedactionPolicy::default());
+
+        let expected = Url::Absolute(AbsoluteUrl::parse("https://example.com/app").unwrap());
+        assert_eq!(manifest.start_url, expected);
+        assert_eq!(manifest.id, Some(expected));
+    }
+
+    #[test]
+    fn test_redact_document() {
+        let mut document = document();
+        redact_document(&mut document, &RedactionPolicy::default());
+
+        assert_eq!(document, json!({
+            "name": "Example",
+            "start_url": "https://example.com/",
+            "related_applications": [{ "platform": "play", "id": "com.example.app" }]
+        }));
+
+        let mut document = self::document();
+        let policy = RedactionPolicy {
+            remove_fingerprints: false,
+            remove_screenshots: false,
+            anonymize_start_url: false,
+            remove_push_members: false,
+            remove_vendor_members: false,
+            ..Default::default()
+        };
+        redact_document(&mut document, &policy);
+        assert_eq!(document, self::document());
+    }
+}
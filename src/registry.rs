@@ -0,0 +1,236 @@
+//! Contains the registry of installed web applications.
+//!
+//! The registry holds processed manifests of many web applications and can be
+//! shared between threads. It supports lookup of the application that a URL
+//! belongs to and notifies subscribers about changes.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::types::*;
+use crate::{is_within_scope, WebAppManifest};
+
+/// A change of the registry.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RegistryEvent {
+    /// A new application was inserted.
+    Inserted(String),
+
+    /// The manifest of an existing application was updated.
+    Updated(String),
+
+    /// The application was removed.
+    Removed(String),
+}
+
+/// A thread-safe registry of processed manifests keyed by their app IDs.
+///
+/// Manifests are stored behind [`Arc`], so lookups do not need to clone them
+/// and returned manifests stay valid even if they are later replaced.
+#[derive(Debug, Default)]
+pub struct ManifestRegistry {
+    manifests: RwLock<HashMap<String, Arc<WebAppManifest>>>,
+    subscribers: Mutex<Vec<Sender<RegistryEvent>>>,
+}
+
+impl ManifestRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts or updates the manifest of the application.
+    ///
+    /// The manifest should be [processed][WebAppManifest::process], as unprocessed
+    /// manifests cannot be found by URL.
+    ///
+    /// # Returns
+    ///
+    /// The previous manifest of the application, if any.
+    ///
+    pub fn insert(
+        &self,
+        id: impl Into<String>,
+        manifest: WebAppManifest,
+    ) -> Option<Arc<WebAppManifest>> {
+        let id = id.into();
+        let previous = self.write().insert(id.clone(), Arc::new(manifest));
+
+        self.notify(match previous {
+            Some(_) => RegistryEvent::Updated(id),
+            None => RegistryEvent::Inserted(id),
+        });
+
+        previous
+    }
+
+    /// Removes the application from the registry.
+    ///
+    /// # Returns
+    ///
+    /// The removed manifest, if the application was registered.
+    ///
+    pub fn remove(&self, id: &str) -> Option<Arc<WebAppManifest>> {
+        let removed = self.write().remove(id);
+
+        if removed.is_some() {
+            self.notify(RegistryEvent::Removed(id.to_string()));
+        }
+
+        removed
+    }
+
+    /// Returns the manifest of the application.
+    pub fn get(&self, id: &str) -> Option<Arc<WebAppManifest>> {
+        self.read().get(id).cloned()
+    }
+
+    /// Returns the IDs of all registered applications.
+    pub fn ids(&self) -> Vec<String> {
+        self.read().keys().cloned().collect()
+    }
+
+    /// Returns the number of registered applications.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Checks whether no applications are registered.
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Finds the application whose scope contains the URL.
+    ///
+    /// If the URL is within scopes of multiple applications, the application with
+    /// the most specific (longest) scope is returned. Applications whose scope
+    /// patterns match the URL are only returned if no scope contains it.
+    ///
+    /// # Returns
+    ///
+    /// The ID and the manifest of the application, if any.
+    ///
+    pub fn find_by_url(&self, url: &AbsoluteUrl) -> Option<(String, Arc<WebAppManifest>)> {
+        let manifests = self.read();
+
+        manifests
+            .iter()
+            .filter(|(_, manifest)| manifest.is_within_scope(url))
+            .map(|(id, manifest)| match &manifest.scope {
+                Url::Absolute(scope) if is_within_scope(url, scope) => {
+                    (scope.as_str().len(), id, manifest)
+                }
+                // URLs matched only by scope patterns are preferred least
+                _ => (0, id, manifest),
+            })
+            .max_by(|(a_length, a_id, _), (b_length, b_id, _)| {
+                // Ties are broken by ID, so the result does not depend on the map order
+                a_length.cmp(b_length).then_with(|| b_id.cmp(a_id))
+            })
+            .map(|(_, id, manifest)| (id.clone(), manifest.clone()))
+    }
+
+    /// Subscribes to changes of the registry.
+    ///
+    /// Events are sent after each change. Subscribers are removed once their
+    /// receivers are dropped.
+    pub fn subscribe(&self) -> Receiver<RegistryEvent> {
+        let (sender, receiver) = channel();
+        self.subscribers.lock().unwrap_or_else(|error| error.into_inner()).push(sender);
+        receiver
+    }
+
+    fn notify(&self, event: RegistryEvent) {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|error| error.into_inner());
+        subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Arc<WebAppManifest>>> {
+        self.manifests.read().unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Arc<WebAppManifest>>> {
+        self.manifests.write().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn manifest(scope: &str) -> WebAppManifest {
+        let scope = AbsoluteUrl::parse(scope).unwrap();
+        WebAppManifest {
+            start_url: Url::Absolute(scope.clone()),
+            scope: Url::Absolute(scope),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_by_url() {
+        let registry = ManifestRegistry::new();
+        registry.insert("root", manifest("https://example.com/"));
+        registry.insert("app", manifest("https://example.com/app/"));
+        registry.insert("other", manifest("https://other.example.com/"));
+
+        let url = |url: &str| AbsoluteUrl::parse(url).unwrap();
+
+        assert_eq!(registry.find_by_url(&url("https://example.com/app/page")).unwrap().0, "app");
+        assert_eq!(registry.find_by_url(&url("https://example.com/application")).unwrap().0, "root");
+        assert_eq!(registry.find_by_url(&url("https://other.example.com/x")).unwrap().0, "other");
+        assert!(registry.find_by_url(&url("https://unknown.example.com/")).is_none());
+    }
+
+    #[test]
+    fn test_notifications() {
+        let registry = ManifestRegistry::new();
+        let events = registry.subscribe();
+
+        registry.insert("app", manifest("https://example.com/"));
+        registry.insert("app", manifest("https://example.com/app/"));
+        registry.remove("app");
+        registry.remove("app");
+
+        let events: Vec<_> = events.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                RegistryEvent::Inserted("app".to_string()),
+                RegistryEvent::Updated("app".to_string()),
+                RegistryEvent::Removed("app".to_string()),
+            ]
+        );
+
+        drop(registry.subscribe());
+        registry.insert("app", manifest("https://example.com/"));
+        assert_eq!(registry.subscribers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_access() {
+        let registry = Arc::new(ManifestRegistry::new());
+
+        let handles: Vec<_> = (0..4)
+            .map(|index| {
+                let registry = registry.clone();
+                thread::spawn(move || {
+                    let scope = format!("https://example{}.com/", index);
+                    registry.insert(index.to_string(), manifest(&scope));
+                    registry.find_by_url(&AbsoluteUrl::parse(&scope).unwrap()).is_some()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+
+        assert_eq!(registry.len(), 4);
+    }
+}
@@ -1,12 +1,18 @@
 //! Contains all manifest resources.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
-use serde_with::formats::SpaceSeparator;
-use serde_with::{serde_as, skip_serializing_none, DisplayFromStr, StringWithSeparator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::formats::PreferMany;
+use serde_with::{
+    serde_as, skip_serializing_none, DeserializeAs, DisplayFromStr, OneOrMany, SerializeAs,
+};
 use smart_default::SmartDefault;
 
+use crate::pattern::UrlPatternInit;
 use crate::types::*;
 
 /// A fingerprint represents a set of cryptographic fingerprints used for verifying the application.
@@ -41,8 +47,11 @@ pub struct ExternalApplicationResource {
     /// The `platform` field represents the platform this external application resource is
     /// associated with. A platform represents a software distribution ecosystem or an
     /// operating system. The specification does not define the particular values for
-    /// the platform member.
-    pub platform: String,
+    /// the platform member, but known values are parsed into the corresponding
+    /// [`Platform`] variants.
+    #[default(Platform::Other(String::new()))]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub platform: Platform,
 
     /// The `min_version` field represents the minimum version of the application that is
     /// considered related to this web app. This version is a string with platform-specific
@@ -79,9 +88,82 @@ pub struct ProtocolHandlerResource {
     /// The `url` field contains the URL within the application scope that will handle
     /// the protocol. The `%s` token should be replaced by the URL starting with the
     /// protocol handler's scheme.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
     pub url: Url,
 }
 
+/// A file handler resource represents a type of files that application can open.
+///
+/// # See also
+///
+/// - [Specification](https://wicg.github.io/manifest-incubations/#file_handlers-member)
+///
+#[skip_serializing_none]
+#[serde_as]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct FileHandlerResource {
+    /// The `action` field contains the URL within the application scope that will
+    /// handle the files.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
+    pub action: Url,
+
+    /// The `name` field represents the name of the file type as it is usually
+    /// displayed to the user.
+    pub name: Option<String>,
+
+    /// The `icons` field serves as iconic representations of the file type in various contexts.
+    pub icons: Vec<IconResource>,
+
+    /// The `accept` field maps media types to the file extensions that the handler accepts.
+    /// Media types may contain wildcards, such as `image/*`.
+    #[serde_as(as = "BTreeMap<_, OneOrMany<_, PreferMany>>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "BTreeMap<String, Vec<String>>"))]
+    pub accept: BTreeMap<String, Vec<String>>,
+
+    /// The `launch_type` field specifies whether multiple files should be opened in
+    /// a single or multiple application clients.
+    pub launch_type: FileHandlerLaunchType,
+}
+
+impl FileHandlerResource {
+    /// Checks whether the file handler accepts files of the provided media type.
+    ///
+    /// Wildcards in the accepted media types (such as `image/*` or `*/*`) are supported.
+    pub fn accepts(&self, media_type: &MediaType) -> bool {
+        self.accept.keys().any(|accepted| media_type_matches(accepted, media_type))
+    }
+}
+
+/// A launch handler resource represents how the application should be launched.
+///
+/// # See also
+///
+/// - [Specification](https://wicg.github.io/web-app-launch/#launch_handler-member)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct LaunchHandlerResource {
+    /// The `client_mode` field contains the client mode or the ordered list of client
+    /// modes, from which the first supported one is used.
+    ///
+    /// Use the [`resolved_client_mode`][LaunchHandlerResource::resolved_client_mode]
+    /// method to get the client mode that should be used.
+    pub client_mode: ClientModes,
+}
+
+impl LaunchHandlerResource {
+    /// Returns the client mode that should be used, which is the first supported value
+    /// in the [`client_mode`][LaunchHandlerResource::client_mode] list, or
+    /// [`ClientMode::Auto`] if there is no supported value.
+    pub fn resolved_client_mode(&self) -> ClientMode {
+        self.client_mode.resolve()
+    }
+}
+
 /// A shortcut resource represents a link to a key task or page within a web app.
 ///
 /// # See also
@@ -108,6 +190,7 @@ pub struct ShortcutResource {
 
     /// The `url` field stores the URL within the application scope that opens when
     /// the shortcut is activated.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
     pub url: Url,
 
     /// The `icons` field serves as iconic representations of the shortcut in various contexts.
@@ -136,6 +219,45 @@ pub struct ShareTargetParams {
     /// The `url` field specifies the name of the query parameter used for
     /// the URL string referring to a resource being shared.
     pub url: Option<String>,
+
+    /// The `files` field specifies which files are accepted by the share target
+    /// and the names of the form fields they are sent in.
+    pub files: Vec<ShareTargetFiles>,
+}
+
+/// The share target files represent the files accepted by the share target.
+///
+/// # See also
+///
+/// - [Specification](https://w3c.github.io/web-share-target/#sharetargetfiles-and-its-members)
+///
+#[skip_serializing_none]
+#[serde_as]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ShareTargetFiles {
+    /// The `name` field specifies the name of the form field used to share the files.
+    pub name: String,
+
+    /// The `accept` field specifies the accepted media types or file extensions.
+    /// Media types may contain wildcards, such as `image/*`.
+    #[serde_as(as = "OneOrMany<_, PreferMany>")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<String>"))]
+    pub accept: Vec<String>,
+}
+
+impl ShareTargetFiles {
+    /// Checks whether the files of the provided media type are accepted.
+    ///
+    /// Wildcards in the accepted media types (such as `image/*` or `*/*`) are supported.
+    /// Accepted file extensions are ignored.
+    pub fn accepts(&self, media_type: &MediaType) -> bool {
+        self.accept
+            .iter()
+            .filter(|accepted| !accepted.starts_with('.'))
+            .any(|accepted| media_type_matches(accepted, media_type))
+    }
 }
 
 /// The share target represents how the application receives share data.
@@ -151,6 +273,7 @@ pub struct ShareTargetParams {
 #[serde(default)]
 pub struct ShareTargetResource {
     /// The `action` field specifies the URL for the web share target.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
     pub action: Url,
 
     /// The `method` field specifies the HTTP request method for the web share target.
@@ -168,6 +291,32 @@ pub struct ShareTargetResource {
     pub params: ShareTargetParams,
 }
 
+impl ShareTargetResource {
+    /// Checks whether the share target accepts files of the provided media type.
+    ///
+    /// See [`ShareTargetFiles::accepts`] for details about matching.
+    pub fn accepts(&self, media_type: &MediaType) -> bool {
+        self.params.files.iter().any(|files| files.accepts(media_type))
+    }
+}
+
+/// The note-taking resource describes how the application integrates with the note-taking
+/// features of the operating system.
+///
+/// # See also
+///
+/// - [Specification](https://wicg.github.io/manifest-incubations/#note_taking-member)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct NoteTakingResource {
+    /// The `new_note_url` field specifies the URL within the application scope that is
+    /// opened when the user wants to take a new note.
+    pub new_note_url: Option<Url>,
+}
+
 /// An icon resource represents an image resource that is conceptually part of a
 /// web application, suitable to use in various contexts, such as application menu.
 ///
@@ -182,6 +331,7 @@ pub struct ShareTargetResource {
 #[serde(default)]
 pub struct IconResource {
     /// The `src` field stores the path to the image file.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
     pub src: Url,
 
     /// The `type` field serves as a hint as to the media type of the image. Its
@@ -192,19 +342,105 @@ pub struct IconResource {
 
     /// The `sizes` field contains image dimensions. It allows a user agent to
     /// quickly ignore images with incorrect sizes for the purpose.
+    ///
+    /// Sizes that cannot be parsed are ignored.
     #[default([ImageSize::default()].iter().cloned().collect())]
-    #[serde_as(as = "StringWithSeparator::<SpaceSeparator, ImageSize>")]
+    #[serde_as(as = "SpaceSeparatedTokens")]
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub sizes: HashSet<ImageSize>,
 
     /// The `purpose` field defines the purposes of the image.
+    ///
+    /// Unknown purposes are ignored. If none of the purposes is known, the set is empty
+    /// and the icon is never selected for any purpose.
     #[default([ImagePurpose::default()].iter().cloned().collect())]
-    #[serde_as(as = "StringWithSeparator::<SpaceSeparator, ImagePurpose>")]
+    #[serde_as(as = "SpaceSeparatedTokens")]
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub purpose: HashSet<ImagePurpose>,
 
     /// The `label` field represents the accessible name of the image.
     pub label: Option<String>,
+
+    /// The `density` field represents the pixel density of the image. For example,
+    /// a 96x96 pixels icon with a density of `2.0` is meant to be displayed at
+    /// 48x48 CSS pixels.
+    ///
+    /// *Note:* This field has been removed from the specification, but it is still
+    /// present in some older manifests and is used as a hint when selecting icons.
+    pub density: Option<Density>,
+
+    /// The `integrity` field contains the [subresource integrity](https://w3c.github.io/webappsec-subresource-integrity/)
+    /// metadata of the image, which allows verifying that cached images were not modified.
+    ///
+    /// *Note:* This field is not described in the specification and is not standardized. It is
+    /// meant to be stored with the fetched manifest and is computed and verified with the methods
+    /// enabled by the `integrity` feature.
+    pub integrity: Option<String>,
+}
+
+impl IconResource {
+    /// Selects the icon that best matches the requested size on a screen with the given
+    /// device pixel ratio, similarly to how browsers choose launcher icons.
+    ///
+    /// Only icons with the requested purpose are considered. The requested size in CSS
+    /// pixels is converted to physical pixels, and the icon with the closest size is chosen,
+    /// preferring the exact size or an icon with any size, then the smallest larger icon
+    /// (which can be downscaled without losing quality), and then the largest smaller icon.
+    /// When multiple icons are equally good, the one whose legacy
+    /// [`density`][IconResource::density] is the closest to the device pixel ratio wins.
+    ///
+    /// # Parameters
+    ///
+    /// - `icons`: The icons to select from.
+    /// - `size`: The target size of the icon in CSS pixels.
+    /// - `device_pixel_ratio`: The ratio between physical and CSS pixels of the screen.
+    /// - `purpose`: The purpose the icon will be used for.
+    ///
+    pub fn select(
+        icons: &[IconResource],
+        size: u32,
+        device_pixel_ratio: f64,
+        purpose: ImagePurpose,
+    ) -> Option<&IconResource> {
+        let target = (size as f64 * device_pixel_ratio).ceil() as u32;
+
+        icons
+            .iter()
+            .filter(|icon| icon.purpose.contains(&purpose))
+            .filter_map(|icon| icon.size_score(target).map(|score| (icon, score)))
+            .min_by(|(icon1, score1), (icon2, score2)| {
+                score1.cmp(score2).then_with(|| {
+                    let distance1 =
+                        (icon1.density.map_or(1.0, f64::from) - device_pixel_ratio).abs();
+                    let distance2 =
+                        (icon2.density.map_or(1.0, f64::from) - device_pixel_ratio).abs();
+                    distance1.total_cmp(&distance2)
+                })
+            })
+            .map(|(icon, _)| icon)
+    }
+
+    /// Scores how well the icon sizes match the target size in physical pixels.
+    /// Lower scores are better.
+    fn size_score(&self, target: u32) -> Option<(u8, u32)> {
+        self.sizes
+            .iter()
+            .map(|size| match *size {
+                ImageSize::Any => (0, 0),
+                ImageSize::Fixed(width, height) => {
+                    let actual = width.max(height);
+
+                    if actual == target {
+                        (0, 0)
+                    } else if actual > target {
+                        (1, actual - target)
+                    } else {
+                        (2, target - actual)
+                    }
+                }
+            })
+            .min()
+    }
 }
 
 /// A screenshots resource represents an image resource, representing the web
@@ -221,6 +457,7 @@ pub struct IconResource {
 #[serde(default)]
 pub struct ScreenshotResource {
     /// The `src` field stores the path to the image file.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
     pub src: Url,
 
     /// The `type` field serves as a hint as to the media type of the image. Its
@@ -231,8 +468,10 @@ pub struct ScreenshotResource {
 
     /// The `sizes` field contains image dimensions. It allows a user agent to
     /// quickly ignore images with incorrect sizes for the purpose.
+    ///
+    /// Sizes that cannot be parsed are ignored.
     #[default([ImageSize::default()].iter().cloned().collect())]
-    #[serde_as(as = "StringWithSeparator::<SpaceSeparator, ImageSize>")]
+    #[serde_as(as = "SpaceSeparatedTokens")]
     #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub sizes: HashSet<ImageSize>,
 
@@ -240,10 +479,399 @@ pub struct ScreenshotResource {
     /// given screenshot applies. User agents may show as many screenshots as
     /// they choose, but should not display screenshots that do not pertain
     /// to their platform.
-    pub platform: Option<String>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub platform: Option<Platform>,
+
+    /// The `form_factor` field represents the screen form factor for which
+    /// a given screenshot applies. Screenshots without a form factor apply
+    /// to all form factors.
+    pub form_factor: Option<FormFactor>,
 
     /// The `label` field represents the accessible name of the image.
     pub label: Option<String>,
+
+    /// The `integrity` field contains the subresource integrity metadata of the image.
+    ///
+    /// *Note:* This field is not standardized. See [`IconResource::integrity`] for details.
+    pub integrity: Option<String>,
+}
+
+impl ScreenshotResource {
+    /// Selects screenshots that should be displayed in a store listing for the given
+    /// platform and form factor.
+    ///
+    /// Screenshots that target a different platform or form factor are excluded.
+    /// Screenshots with an unknown form factor are treated as generic.
+    /// Screenshots that explicitly target the requested platform and form factor are
+    /// ordered before generic screenshots, while the manifest order is otherwise
+    /// preserved. At most `max_count` screenshots are returned.
+    ///
+    /// # Parameters
+    ///
+    /// - `screenshots`: Screenshots to select from.
+    /// - `platform`: The distribution platform, or `None` to only select generic screenshots.
+    /// - `form_factor`: The form factor, or `None` to ignore form factors.
+    /// - `max_count`: The maximum number of returned screenshots.
+    ///
+    pub fn select<'a>(
+        screenshots: &'a [ScreenshotResource],
+        platform: Option<&Platform>,
+        form_factor: Option<&FormFactor>,
+        max_count: usize,
+    ) -> Vec<&'a ScreenshotResource> {
+        // Unknown form factors are ignored, as in user agents
+        let generic_form_factor = |screenshot: &ScreenshotResource| {
+            matches!(screenshot.form_factor, None | Some(FormFactor::Unknown(_)))
+        };
+
+        let mut selected: Vec<_> = screenshots
+            .iter()
+            .filter(|screenshot| {
+                screenshot.platform.is_none() || screenshot.platform.as_ref() == platform
+            })
+            .filter(|screenshot| {
+                form_factor.is_none()
+                    || generic_form_factor(screenshot)
+                    || screenshot.form_factor.as_ref() == form_factor
+            })
+            .collect();
+
+        // Stable sort keeps the manifest order within each group
+        selected.sort_by_key(|screenshot| {
+            let generic_platform = screenshot.platform.is_none();
+            (generic_platform, form_factor.is_some() && generic_form_factor(screenshot))
+        });
+
+        selected.truncate(max_count);
+        selected
+    }
+}
+
+/// A translation resource represents the localized values of the manifest members for
+/// a specific language.
+///
+/// Translated shortcuts are matched with the manifest shortcuts by their position, so the
+/// first translated shortcut applies to the first shortcut, etc. Translated icons replace
+/// the manifest icons, so they can contain localized text.
+///
+/// # See also
+///
+/// - [Explainer](https://github.com/WICG/manifest-incubations/blob/gh-pages/translations-explainer.md)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct TranslationResource {
+    /// The `name` field contains the translated [`name`][crate::WebAppManifest::name].
+    pub name: Option<String>,
+
+    /// The `short_name` field contains the translated [`short_name`][crate::WebAppManifest::short_name].
+    pub short_name: Option<String>,
+
+    /// The `description` field contains the translated [`description`][crate::WebAppManifest::description].
+    pub description: Option<String>,
+
+    /// The `shortcuts` field contains the translations of the manifest shortcuts.
+    pub shortcuts: Vec<ShortcutTranslation>,
+
+    /// The `icons` field contains the icons that replace the manifest [`icons`][crate::WebAppManifest::icons]
+    /// for the language. Their URLs are resolved when the manifest is processed.
+    pub icons: Vec<IconResource>,
+}
+
+/// A shortcut translation represents the localized values of a shortcut.
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ShortcutTranslation {
+    /// The `name` field contains the translated [`name`][ShortcutResource::name].
+    pub name: Option<String>,
+
+    /// The `short_name` field contains the translated [`short_name`][ShortcutResource::short_name].
+    pub short_name: Option<String>,
+
+    /// The `description` field contains the translated [`description`][ShortcutResource::description].
+    pub description: Option<String>,
+}
+
+/// A user preferences resource represents the overrides of manifest members that are
+/// applied depending on the preferences of the user.
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct UserPreferencesResource {
+    /// The `color_scheme_dark` field contains the overrides that are applied when the
+    /// user prefers a dark color scheme.
+    pub color_scheme_dark: Option<ColorSchemeOverrides>,
+
+    /// The `color_scheme_light` field contains the overrides that are applied when the
+    /// user prefers a light color scheme.
+    pub color_scheme_light: Option<ColorSchemeOverrides>,
+}
+
+impl UserPreferencesResource {
+    /// Returns the overrides that are applied for the provided color scheme.
+    pub fn color_scheme(&self, color_scheme: ColorScheme) -> Option<&ColorSchemeOverrides> {
+        match color_scheme {
+            ColorScheme::Light => self.color_scheme_light.as_ref(),
+            ColorScheme::Dark => self.color_scheme_dark.as_ref(),
+        }
+    }
+}
+
+/// The color scheme overrides represent the colors that replace the manifest colors
+/// when the user prefers a specific color scheme.
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ColorSchemeOverrides {
+    /// The `theme_color` field overrides the [`theme_color`][crate::WebAppManifest::theme_color]
+    /// member of the manifest.
+    #[serde(deserialize_with = "crate::empty_string_as_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub theme_color: Option<Color>,
+
+    /// The `background_color` field overrides the
+    /// [`background_color`][crate::WebAppManifest::background_color] member of the manifest.
+    #[serde(deserialize_with = "crate::empty_string_as_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub background_color: Option<Color>,
+}
+
+/// A tab strip resource represents how the application tab strip is displayed when
+/// the application is opened in the `tabbed` display mode.
+///
+/// # See also
+///
+/// - [Explainer](https://github.com/WICG/manifest-incubations/blob/gh-pages/tabbed-mode-explainer.md)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct TabStripResource {
+    /// The `home_tab` field describes the home tab, which is pinned to the tab strip and
+    /// contains the pages within its scope patterns. If not present, the application does
+    /// not have a home tab.
+    pub home_tab: Option<HomeTabParams>,
+
+    /// The `new_tab_button` field describes the button that opens a new application tab.
+    pub new_tab_button: Option<NewTabButtonParams>,
+}
+
+/// The home tab params represent how the home tab of the tab strip is displayed.
+///
+/// # See also
+///
+/// - [Explainer](https://github.com/WICG/manifest-incubations/blob/gh-pages/tabbed-mode-explainer.md)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct HomeTabParams {
+    /// The `icons` field serves as iconic representations of the home tab.
+    pub icons: Vec<IconResource>,
+
+    /// The `scope_patterns` field contains the URL patterns of pages that are opened
+    /// in the home tab. Navigations to other pages open in a new tab.
+    pub scope_patterns: Vec<UrlPatternInit>,
+}
+
+/// The new tab button params represent how the new tab button of the tab strip behaves.
+///
+/// # See also
+///
+/// - [Explainer](https://github.com/WICG/manifest-incubations/blob/gh-pages/tabbed-mode-explainer.md)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct NewTabButtonParams {
+    /// The `url` field stores the URL within the application scope that opens when the
+    /// new tab button is clicked. If not present, the start URL is used.
+    pub url: Option<Url>,
+}
+
+/// A service worker resource represents a service worker that the user agent registers
+/// when the application is installed.
+///
+/// *Note:* This member has been removed from the specification, and service workers
+/// should be registered by the document instead. It is only supported so that older
+/// manifests do not lose data.
+///
+/// # See also
+///
+/// - [Specification (2017)](https://www.w3.org/TR/2017/WD-appmanifest-20171129/#serviceworker-member)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ServiceWorkerResource {
+    /// The `src` field stores the URL of the service worker script.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
+    pub src: Url,
+
+    /// The `scope` field stores the scope URL of the service worker registration.
+    pub scope: Option<Url>,
+
+    /// The `type` field contains the type of the service worker script.
+    pub r#type: Option<WorkerType>,
+
+    /// The `update_via_cache` field specifies whether the HTTP cache is used when
+    /// updating the service worker.
+    pub update_via_cache: Option<UpdateViaCache>,
+}
+
+/// A scope extension resource represents an additional origin in which the application
+/// may operate.
+///
+/// # See also
+///
+/// - [Explainer](https://github.com/WICG/manifest-incubations/blob/gh-pages/scope_extensions-explainer.md)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ScopeExtensionResource {
+    /// The `origin` field contains the additional origin. The host may start with a `*.`
+    /// wildcard prefix to also match all subdomains.
+    ///
+    /// The origin is validated and normalized when calling [`process`][crate::WebAppManifest::process].
+    pub origin: String,
+}
+
+/// A URL handler resource represents an origin whose URLs the application handles.
+///
+/// *Note:* This member has been superseded by the `scope_extensions` member. It is only
+/// supported so that older manifests can be parsed and migrated.
+///
+/// # See also
+///
+/// - [Explainer](https://github.com/WICG/pwa-url-handler/blob/main/explainer.md)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct UrlHandlerResource {
+    /// The `origin` field contains the handled origin. The host may start with a `*.`
+    /// wildcard prefix to also match all subdomains.
+    pub origin: String,
+}
+
+/// A widget resource represents a widget that the web application provides to the
+/// widgets board of the operating system.
+///
+/// *Note:* Widgets are not described in the specification and are not standardized.
+/// They are currently only supported by Microsoft Edge on Windows 11.
+///
+/// # See also
+///
+/// - [Microsoft Docs](https://learn.microsoft.com/en-us/microsoft-edge/progressive-web-apps-chromium/how-to/widgets)
+///
+#[skip_serializing_none]
+#[derive(SmartDefault, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct WidgetResource {
+    /// The `name` field represents the title of the widget, as it is displayed to
+    /// the user in the widgets board.
+    pub name: String,
+
+    /// The `short_name` field represents the short version of the widget name. It is
+    /// used where there is insufficient space to display the full name of the widget.
+    pub short_name: Option<String>,
+
+    /// The `description` field describes what the widget does.
+    pub description: Option<String>,
+
+    /// The `tag` field contains a string that uniquely identifies the widget within
+    /// the application and is used by the service worker to refer to the widget.
+    pub tag: String,
+
+    /// The `template` field contains the name of the template used to render the
+    /// widget, which is used by the service worker to look up the widget.
+    pub template: Option<String>,
+
+    /// The `ms_ac_template` field stores the URL of the Adaptive Cards template
+    /// used to render the widget.
+    #[serde(skip_serializing_if = "Url::is_unknown")]
+    pub ms_ac_template: Url,
+
+    /// The `data` field stores the URL of the data that is combined with the template.
+    pub data: Option<Url>,
+
+    /// The `screenshots` field contains the screenshots of the widget, which are
+    /// displayed in the widget picker.
+    pub screenshots: Vec<ScreenshotResource>,
+
+    /// The `icons` field serves as iconic representations of the widget in various contexts.
+    pub icons: Vec<IconResource>,
+
+    /// The `auth` field specifies whether the widget requires the user to be authenticated.
+    pub auth: bool,
+
+    /// The `update` field specifies how often, in seconds, the widget should be updated.
+    pub update: Option<u64>,
+}
+
+/// Serializes a set as a space-separated string and deserializes it while ignoring the
+/// tokens that cannot be parsed, as user agents do.
+///
+/// Some manifest generators emit the tokens as an array of strings, so arrays are also
+/// accepted when deserializing. They are serialized back to the canonical string form.
+pub(crate) struct SpaceSeparatedTokens;
+
+impl<T: fmt::Display> SerializeAs<HashSet<T>> for SpaceSeparatedTokens {
+    fn serialize_as<S: Serializer>(source: &HashSet<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        let tokens: Vec<_> = source.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&tokens.join(" "))
+    }
+}
+
+impl<'de, T: FromStr + Eq + Hash> DeserializeAs<'de, HashSet<T>> for SpaceSeparatedTokens {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<HashSet<T>, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Tokens {
+            String(String),
+            Array(Vec<String>),
+        }
+
+        let tokens = match Tokens::deserialize(deserializer)? {
+            Tokens::String(string) => string,
+            Tokens::Array(array) => array.join(" "),
+        };
+
+        Ok(tokens.split_ascii_whitespace().filter_map(|token| token.parse().ok()).collect())
+    }
+}
+
+/// Checks whether the media type matches the accepted media type, which may contain wildcards.
+///
+/// Parameters of both media types are ignored and the comparison is case-insensitive.
+fn media_type_matches(accepted: &str, media_type: &MediaType) -> bool {
+    let essence = |value: &str| value.split(';').next().unwrap_or_default().trim().to_lowercase();
+
+    let accepted = essence(accepted);
+    let actual = essence(&media_type.to_string());
+
+    let (Some((accepted_type, accepted_subtype)), Some((actual_type, actual_subtype))) =
+        (accepted.split_once('/'), actual.split_once('/'))
+    else {
+        return false;
+    };
+
+    (accepted_type == "*" || accepted_type == actual_type)
+        && (accepted_subtype == "*" || accepted_subtype == actual_subtype)
 }
 
 #[cfg(test)]
@@ -268,6 +896,56 @@ mod tests {
         assert_eq!(deserialized.enctype, ShareTargetEnctype::FormData);
     }
 
+    #[test]
+    fn test_icon_invalid_sizes() {
+        let serialized = r#"{"src":"icon.png","sizes":"48x48 96 x 128X128"}"#;
+        let deserialized: IconResource = serde_json::from_str(serialized).unwrap();
+
+        let expected: HashSet<_> =
+            [ImageSize::Fixed(48, 48), ImageSize::Fixed(128, 128)].iter().cloned().collect();
+        assert_eq!(deserialized.sizes, expected);
+
+        let serialized = r#"{"src":"screenshot.png","sizes":"1280"}"#;
+        let deserialized: ScreenshotResource = serde_json::from_str(serialized).unwrap();
+        assert!(deserialized.sizes.is_empty());
+    }
+
+    #[test]
+    fn test_icon_sizes_array() {
+        let serialized = r#"{"src":"icon.png","sizes":["48x48","96x96 invalid"]}"#;
+        let deserialized: IconResource = serde_json::from_str(serialized).unwrap();
+
+        let expected: HashSet<_> =
+            [ImageSize::Fixed(48, 48), ImageSize::Fixed(96, 96)].iter().cloned().collect();
+        assert_eq!(deserialized.sizes, expected);
+
+        let icon = IconResource {
+            sizes: [ImageSize::Fixed(48, 48)].iter().cloned().collect(),
+            ..deserialized
+        };
+        let reserialized = serde_json::to_value(&icon).unwrap();
+        assert_eq!(reserialized["sizes"], "48x48");
+
+        let serialized = r#"{"src":"screenshot.png","sizes":["1280x720"]}"#;
+        let deserialized: ScreenshotResource = serde_json::from_str(serialized).unwrap();
+        assert!(deserialized.sizes.contains(&ImageSize::Fixed(1280, 720)));
+    }
+
+    #[test]
+    fn test_icon_unknown_purposes() {
+        let serialized = r#"{"src":"icon.png","purpose":"any  fancy\tMASKABLE"}"#;
+        let deserialized: IconResource = serde_json::from_str(serialized).unwrap();
+
+        let expected: HashSet<_> =
+            [ImagePurpose::Any, ImagePurpose::Maskable].iter().cloned().collect();
+        assert_eq!(deserialized.purpose, expected);
+
+        let deserialized: IconResource =
+            serde_json::from_str(r#"{"src":"icon.png","purpose":"fancy"}"#).unwrap();
+        assert!(deserialized.purpose.is_empty());
+        assert_eq!(IconResource::select(&[deserialized], 48, 1.0, ImagePurpose::Any), None);
+    }
+
     #[test]
     fn test_icon_sizes() {
         let icon = IconResource {
@@ -339,4 +1017,225 @@ mod tests {
         assert!(deserialized.sizes.contains(&ImageSize::Fixed(256, 512)));
         assert!(deserialized.sizes.contains(&ImageSize::Fixed(1024, 2048)));
     }
+
+    fn screenshot(
+        src: &str,
+        platform: Option<Platform>,
+        form_factor: Option<FormFactor>,
+    ) -> ScreenshotResource {
+        ScreenshotResource {
+            src: Url::Relative(src.to_string()),
+            platform,
+            form_factor,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_screenshots() {
+        let screenshots = vec![
+            screenshot("generic.png", None, None),
+            screenshot("narrow.png", None, Some(FormFactor::Narrow)),
+            screenshot("wide.png", None, Some(FormFactor::Wide)),
+            screenshot("play-narrow.png", Some(Platform::Play), Some(FormFactor::Narrow)),
+            screenshot("itunes.png", Some(Platform::Itunes), None),
+            screenshot("foldable.png", None, Some(FormFactor::Unknown("foldable".to_string()))),
+        ];
+
+        fn sources(selected: Vec<&ScreenshotResource>) -> Vec<&str> {
+            let sources = selected.into_iter().map(|screenshot| match &screenshot.src {
+                Url::Relative(src) => src.as_str(),
+                _ => unreachable!(),
+            });
+            sources.collect()
+        }
+
+        let selected = ScreenshotResource::select(
+            &screenshots,
+            Some(&Platform::Play),
+            Some(&FormFactor::Narrow),
+            10,
+        );
+        assert_eq!(sources(selected), vec!["play-narrow.png", "narrow.png", "generic.png", "foldable.png"]);
+
+        let selected = ScreenshotResource::select(&screenshots, Some(&Platform::Itunes), None, 10);
+        assert_eq!(sources(selected), vec!["itunes.png", "generic.png", "narrow.png", "wide.png", "foldable.png"]);
+
+        let selected = ScreenshotResource::select(&screenshots, None, Some(&FormFactor::Wide), 1);
+        assert_eq!(sources(selected), vec!["wide.png"]);
+
+        let selected = ScreenshotResource::select(&screenshots, None, None, 0);
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn test_screenshot_form_factor() {
+        let json = r#"{"src":"screenshot.png","form_factor":"narrow"}"#;
+        let deserialized: ScreenshotResource = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.form_factor, Some(FormFactor::Narrow));
+    }
+
+    fn icon(src: &str, size: u32, density: Option<f64>) -> IconResource {
+        IconResource {
+            src: Url::Relative(src.to_string()),
+            sizes: [ImageSize::Fixed(size, size)].iter().cloned().collect(),
+            density: density.map(Density),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_icon_device_pixel_ratio() {
+        let icons =
+            vec![icon("48.png", 48, None), icon("96.png", 96, None), icon("192.png", 192, None)];
+
+        let selected = IconResource::select(&icons, 48, 1.0, ImagePurpose::Any).unwrap();
+        assert_eq!(selected.src, Url::Relative("48.png".to_string()));
+
+        let selected = IconResource::select(&icons, 48, 2.0, ImagePurpose::Any).unwrap();
+        assert_eq!(selected.src, Url::Relative("96.png".to_string()));
+
+        let selected = IconResource::select(&icons, 48, 3.0, ImagePurpose::Any).unwrap();
+        assert_eq!(selected.src, Url::Relative("192.png".to_string()));
+
+        let selected = IconResource::select(&icons, 256, 1.0, ImagePurpose::Any).unwrap();
+        assert_eq!(selected.src, Url::Relative("192.png".to_string()));
+
+        assert_eq!(IconResource::select(&icons, 48, 1.0, ImagePurpose::Maskable), None);
+    }
+
+    #[test]
+    fn test_icon_density() {
+        let json = r#"{"src":"icon.png","density":1.5}"#;
+        let deserialized: IconResource = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.density, Some(Density(1.5)));
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), r#"{"src":"icon.png","sizes":"any","purpose":"any","density":1.5}"#);
+
+        assert_ne!(Density(0.0), Density(-0.0));
+        assert_eq!(Density(f64::NAN), Density(f64::NAN));
+    }
+
+    #[test]
+    fn test_select_icon_density() {
+        let icons = vec![icon("1x.png", 96, Some(1.0)), icon("2x.png", 96, Some(2.0))];
+
+        let selected = IconResource::select(&icons, 48, 2.0, ImagePurpose::Any).unwrap();
+        assert_eq!(selected.src, Url::Relative("2x.png".to_string()));
+
+        let selected = IconResource::select(&icons, 96, 1.0, ImagePurpose::Any).unwrap();
+        assert_eq!(selected.src, Url::Relative("1x.png".to_string()));
+    }
+
+    #[test]
+    fn test_share_target_files_accepts() {
+        let serialized = r#"{"action":"share","params":{"files":[{"name":"images","accept":["image/*",".svg"]},{"name":"text","accept":"text/plain"}]}}"#;
+        let deserialized: ShareTargetResource = serde_json::from_str(serialized).unwrap();
+
+        assert_eq!(deserialized.params.files[1].accept, vec!["text/plain".to_string()]);
+
+        assert!(deserialized.accepts(&MediaType::parse("image/png").unwrap()));
+        assert!(deserialized.accepts(&MediaType::parse("text/plain; charset=utf-8").unwrap()));
+        assert!(!deserialized.accepts(&MediaType::parse("text/html").unwrap()));
+        assert!(!deserialized.accepts(&MediaType::parse("application/json").unwrap()));
+    }
+
+    #[test]
+    fn test_file_handler_accepts() {
+        let serialized =
+            r#"{"action":"open","accept":{"text/csv":".csv","application/*":[".json",".xml"]}}"#;
+        let deserialized: FileHandlerResource = serde_json::from_str(serialized).unwrap();
+
+        assert_eq!(deserialized.accept["text/csv"], vec![".csv".to_string()]);
+        assert_eq!(deserialized.launch_type, FileHandlerLaunchType::SingleClient);
+
+        assert!(deserialized.accepts(&MediaType::parse("text/csv").unwrap()));
+        assert!(deserialized.accepts(&MediaType::parse("application/json").unwrap()));
+        assert!(!deserialized.accepts(&MediaType::parse("text/plain").unwrap()));
+    }
+
+    #[test]
+    fn test_user_preferences() {
+        let serialized =
+            r##"{"color_scheme_dark":{"theme_color":"#000000","background_color":""}}"##;
+        let deserialized: UserPreferencesResource = serde_json::from_str(serialized).unwrap();
+
+        let dark = deserialized.color_scheme(ColorScheme::Dark).unwrap();
+        assert_eq!(dark.theme_color, Some(Color::new(0.0, 0.0, 0.0, 1.0)));
+        assert_eq!(dark.background_color, None);
+        assert_eq!(deserialized.color_scheme(ColorScheme::Light), None);
+    }
+
+    #[test]
+    fn test_tab_strip() {
+        let serialized = r#"{"home_tab":{"icons":[],"scope_patterns":[{"pathname":"/"}]},"new_tab_button":{"url":"/new"}}"#;
+        let deserialized: TabStripResource = serde_json::from_str(serialized).unwrap();
+
+        let home_tab = deserialized.home_tab.as_ref().unwrap();
+        assert_eq!(home_tab.scope_patterns[0].pathname.as_deref(), Some("/"));
+        assert_eq!(deserialized.new_tab_button.as_ref().unwrap().url, Some(Url::Relative("/new".to_string())));
+
+        let reserialized = serde_json::to_string(&deserialized).unwrap();
+        assert_eq!(reserialized, serialized);
+    }
+
+    #[test]
+    fn test_service_worker() {
+        let serialized = r#"{"src":"sw.js","scope":"/","type":"module","update_via_cache":"none"}"#;
+        let deserialized: ServiceWorkerResource = serde_json::from_str(serialized).unwrap();
+
+        assert_eq!(deserialized.src, Url::Relative("sw.js".to_string()));
+        assert_eq!(deserialized.r#type, Some(WorkerType::Module));
+        assert_eq!(deserialized.update_via_cache, Some(UpdateViaCache::None));
+
+        let reserialized = serde_json::to_string(&deserialized).unwrap();
+        assert_eq!(reserialized, serialized);
+    }
+
+    #[test]
+    fn test_widget() {
+        let serialized = r#"{"name":"Agenda","tag":"agenda","ms_ac_template":"widgets/agenda.json","data":"widgets/data/agenda.json","auth":true,"update":900}"#;
+        let deserialized: WidgetResource = serde_json::from_str(serialized).unwrap();
+
+        assert_eq!(deserialized.name, "Agenda");
+        assert_eq!(deserialized.tag, "agenda");
+        assert_eq!(deserialized.ms_ac_template, Url::Relative("widgets/agenda.json".to_string()));
+        assert_eq!(deserialized.data, Some(Url::Relative("widgets/data/agenda.json".to_string())));
+        assert!(deserialized.auth);
+        assert_eq!(deserialized.update, Some(900));
+
+        let reserialized = serde_json::to_string(&deserialized).unwrap();
+        assert_eq!(reserialized, r#"{"name":"Agenda","tag":"agenda","ms_ac_template":"widgets/agenda.json","data":"widgets/data/agenda.json","screenshots":[],"icons":[],"auth":true,"update":900}"#);
+    }
+
+    #[test]
+    fn test_launch_handler_single_client_mode() {
+        let serialized = r#"{"client_mode":"navigate-existing"}"#;
+        let deserialized: LaunchHandlerResource = serde_json::from_str(serialized).unwrap();
+
+        assert_eq!(deserialized.client_mode, ClientModes::Single("navigate-existing".to_string()));
+        assert_eq!(deserialized.resolved_client_mode(), ClientMode::NavigateExisting);
+
+        let reserialized = serde_json::to_string(&deserialized).unwrap();
+        assert_eq!(reserialized, serialized);
+    }
+
+    #[test]
+    fn test_launch_handler_multiple_client_modes() {
+        let serialized = r#"{"client_mode":["focus-anything","focus-existing","auto"]}"#;
+        let deserialized: LaunchHandlerResource = serde_json::from_str(serialized).unwrap();
+
+        assert_eq!(deserialized.client_mode.len(), 3);
+        assert_eq!(deserialized.resolved_client_mode(), ClientMode::FocusExisting);
+
+        let reserialized = serde_json::to_string(&deserialized).unwrap();
+        assert_eq!(reserialized, serialized);
+
+        let deserialized: LaunchHandlerResource =
+            serde_json::from_str(r#"{"client_mode":["navigate-new"]}"#).unwrap();
+        assert_eq!(deserialized.client_mode, ClientModes::from(vec![ClientMode::NavigateNew]));
+
+        let deserialized: LaunchHandlerResource =
+            serde_json::from_str(r#"{"client_mode":["unknown"]}"#).unwrap();
+        assert_eq!(deserialized.resolved_client_mode(), ClientMode::Auto);
+    }
 }
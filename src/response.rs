@@ -0,0 +1,219 @@
+//! Contains serving of manifests with [`http`] crate types.
+//!
+//! [`ManifestResponse`] serializes the manifest with the correct content type, an
+//! entity tag derived from its [fingerprint](WebAppManifest::fingerprint) and cache
+//! headers. It can be converted into an [`http::Response`] for any framework that
+//! uses the `http` crate, and it also implements responder traits of [axum] and
+//! [actix-web] when the `axum` or `actix` features are enabled.
+//!
+//! [axum]: https://docs.rs/axum
+//! [actix-web]: https://docs.rs/actix-web
+
+use http::header::{HeaderValue, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use http::{Request, Response, StatusCode};
+
+use crate::canonical::fingerprint_bytes;
+use crate::data_url::MANIFEST_MEDIA_TYPE;
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+/// The default `Cache-Control` header value of manifest responses.
+///
+/// Manifests may be cached, but need to be revalidated on every use, so updates
+/// are detected by browsers as soon as possible.
+pub const DEFAULT_CACHE_CONTROL: &str = "no-cache";
+
+/// A response that serves the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestResponse {
+    body: String,
+    etag: String,
+    cache_control: String,
+    not_modified: bool,
+}
+
+impl ManifestResponse {
+    /// Creates a response that serves the manifest.
+    ///
+    /// The manifest is serialized to [canonical JSON](WebAppManifest::to_canonical_json),
+    /// so equal manifests always produce equal responses and entity tags. It should
+    /// usually be processed before serving, so clients receive absolute URLs.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn new(manifest: &WebAppManifest) -> Result<Self, ManifestError> {
+        let body = manifest.to_canonical_json()?;
+        let etag = format!("\"{}\"", fingerprint_bytes(body.as_bytes()));
+        Ok(Self {
+            body,
+            etag,
+            cache_control: DEFAULT_CACHE_CONTROL.to_string(),
+            not_modified: false,
+        })
+    }
+
+    /// Sets the `Cache-Control` header value of the response.
+    pub fn with_cache_control(mut self, cache_control: impl Into<String>) -> Self {
+        self.cache_control = cache_control.into();
+        self
+    }
+
+    /// Makes the response conditional on the `If-None-Match` request header value.
+    ///
+    /// If the value matches the entity tag of the manifest, the response is turned
+    /// into a `304 Not Modified` response without a body.
+    ///
+    pub fn with_if_none_match(mut self, if_none_match: Option<&HeaderValue>) -> Self {
+        self.not_modified = if_none_match
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| self.matches(value));
+        self
+    }
+
+    /// Makes the response conditional on the headers of the request.
+    ///
+    /// See [`ManifestResponse::with_if_none_match`] for more details.
+    ///
+    pub fn for_request<B>(self, request: &Request<B>) -> Self {
+        let if_none_match = request.headers().get(IF_NONE_MATCH);
+        self.with_if_none_match(if_none_match)
+    }
+
+    /// Returns the serialized manifest.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Returns the quoted entity tag of the manifest.
+    pub fn etag(&self) -> &str {
+        &self.etag
+    }
+
+    /// Returns the `Cache-Control` header value of the response.
+    pub fn cache_control(&self) -> &str {
+        &self.cache_control
+    }
+
+    /// Returns the status code of the response.
+    pub fn status(&self) -> StatusCode {
+        if self.not_modified {
+            StatusCode::NOT_MODIFIED
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    /// Converts the response into an [`http::Response`] with the body of type `B`.
+    pub fn into_http<B: From<Vec<u8>>>(self) -> Response<B> {
+        let status = self.status();
+
+        let body = if self.not_modified { Vec::new() } else { self.body.into_bytes() };
+        let mut response = Response::new(B::from(body));
+        *response.status_mut() = status;
+
+        let headers = response.headers_mut();
+        if status == StatusCode::OK {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(MANIFEST_MEDIA_TYPE));
+        }
+        if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+            headers.insert(ETAG, etag);
+        }
+        if let Ok(cache_control) = HeaderValue::from_str(&self.cache_control) {
+            headers.insert(CACHE_CONTROL, cache_control);
+        }
+
+        response
+    }
+
+    /// Checks whether the `If-None-Match` header value matches the entity tag.
+    ///
+    /// Entity tags are compared with the weak comparison, as required for `If-None-Match`.
+    ///
+    fn matches(&self, if_none_match: &str) -> bool {
+        if_none_match.split(',').map(str::trim).any(|tag| {
+            let tag = tag.strip_prefix("W/").unwrap_or(tag);
+            tag == "*" || tag == self.etag
+        })
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum_core::response::IntoResponse for ManifestResponse {
+    fn into_response(self) -> axum_core::response::Response {
+        self.into_http::<Vec<u8>>().map(axum_core::body::Body::from)
+    }
+}
+
+#[cfg(feature = "actix")]
+impl actix_web::Responder for ManifestResponse {
+    type Body = String;
+
+    fn respond_to(self, request: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        use actix_web::http::header;
+
+        let if_none_match =
+            request.headers().get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+        if if_none_match.is_some_and(|value| self.matches(value)) {
+            return actix_web::HttpResponse::NotModified()
+                .insert_header((header::ETAG, self.etag))
+                .insert_header((header::CACHE_CONTROL, self.cache_control))
+                .message_body(String::new())
+                .unwrap_or_else(|_| unreachable!());
+        }
+
+        actix_web::HttpResponse::Ok()
+            .content_type(MANIFEST_MEDIA_TYPE)
+            .insert_header((header::ETAG, self.etag))
+            .insert_header((header::CACHE_CONTROL, self.cache_control))
+            .message_body(self.body)
+            .unwrap_or_else(|_| unreachable!())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> WebAppManifest {
+        WebAppManifest { name: Some("Example".to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_manifest_response() {
+        let response = ManifestResponse::new(&manifest()).unwrap();
+        assert_eq!(response.etag(), format!("\"{}\"", manifest().fingerprint().unwrap()));
+
+        let response: Response<Vec<u8>> = response.with_cache_control("max-age=3600").into_http();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/manifest+json");
+        assert_eq!(response.headers()[CACHE_CONTROL], "max-age=3600");
+
+        let manifest: WebAppManifest = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("Example"));
+    }
+
+    #[test]
+    fn test_conditional_response() {
+        let response = ManifestResponse::new(&manifest()).unwrap();
+        let etag = response.etag().to_string();
+
+        let request = Request::builder()
+            .header(IF_NONE_MATCH, format!("\"other\", W/{}", etag))
+            .body(())
+            .unwrap();
+        let response: Response<Vec<u8>> = response.clone().for_request(&request).into_http();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag.as_str());
+        assert!(response.body().is_empty());
+
+        let request = Request::builder().header(IF_NONE_MATCH, "\"other\"").body(()).unwrap();
+        let response: Response<Vec<u8>> =
+            ManifestResponse::new(&manifest()).unwrap().for_request(&request).into_http();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
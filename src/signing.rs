@@ -0,0 +1,98 @@
+//! Contains signing and verification of manifests.
+//!
+//! Manifests are signed with detached Ed25519 signatures over their canonical
+//! serialization, so enterprise deployment tools can ensure that manifests were
+//! not tampered with between publication and installation. Any change of the
+//! manifest data, except for the order of members, invalidates the signature.
+
+use ed25519_dalek::{Signer, Verifier};
+
+use crate::canonical::to_canonical_vec;
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+#[rustfmt::skip] #[doc(no_inline)] pub use ed25519_dalek::Signature;
+#[rustfmt::skip] #[doc(no_inline)] pub use ed25519_dalek::SigningKey;
+#[rustfmt::skip] #[doc(no_inline)] pub use ed25519_dalek::VerifyingKey;
+
+impl WebAppManifest {
+    /// Produces a detached signature of the manifest.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: The private key used for signing.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The signature over the canonical serialization of the manifest.
+    /// - `Err`: An error in case the manifest cannot be serialized.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn sign(&self, key: &SigningKey) -> Result<Signature, ManifestError> {
+        Ok(key.sign(&to_canonical_vec(self)?))
+    }
+
+    /// Verifies the detached signature of the manifest.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: The public key of the signer.
+    /// - `signature`: The signature produced by [`sign`][WebAppManifest::sign].
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: If the signature is valid.
+    /// - `Err`: An error in case the signature is not valid.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::InvalidSignature`] if the signature is not valid.
+    /// - [`ManifestError::Json`] if the manifest cannot be serialized to JSON.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn verify(&self, key: &VerifyingKey, signature: &Signature) -> Result<(), ManifestError> {
+        key.verify(&to_canonical_vec(self)?, signature).map_err(|_| ManifestError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other_key = SigningKey::from_bytes(&[8; 32]);
+
+        let mut manifest =
+            WebAppManifest { name: Some("Example".to_string()), ..Default::default() };
+        let signature = manifest.sign(&key).unwrap();
+
+        assert_matches!(manifest.verify(&key.verifying_key(), &signature), Ok(()));
+        assert_matches!(manifest.verify(&other_key.verifying_key(), &signature), Err(ManifestError::InvalidSignature));
+
+        manifest.name = Some("Tampered".to_string());
+        assert_matches!(manifest.verify(&key.verifying_key(), &signature), Err(ManifestError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_verify_ignores_member_order() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+
+        let first: WebAppManifest =
+            serde_json::from_str(r#"{"name":"Example","short_name":"Ex"}"#).unwrap();
+        let second: WebAppManifest =
+            serde_json::from_str(r#"{"short_name":"Ex","name":"Example"}"#).unwrap();
+
+        let signature = first.sign(&key).unwrap();
+        assert_matches!(second.verify(&key.verifying_key(), &signature), Ok(()));
+    }
+}
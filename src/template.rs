@@ -0,0 +1,161 @@
+//! Contains substitution of template variables in manifests.
+//!
+//! String and URL fields may contain placeholders in the form of `{{name}}`, which
+//! are replaced with variable values before the manifest is processed. This allows
+//! using one manifest template for multiple environments and release versions.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use serde_json::Value;
+
+use crate::errors::ManifestError;
+use crate::WebAppManifest;
+
+impl WebAppManifest {
+    /// Substitutes template variables in string and URL fields of the manifest.
+    ///
+    /// Placeholders have the form of `{{name}}`, with optional whitespace around the
+    /// variable name. Placeholders in absolute URLs may also be percent-encoded, as URL
+    /// parsing encodes braces in paths. URL fields that become valid absolute URLs after
+    /// substitution are parsed again, so the manifest can be processed as usual.
+    ///
+    /// # Parameters
+    ///
+    /// - `variables`: The values of template variables.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The manifest with substituted variables.
+    /// - `Err`: An error in case any of the placeholders is not valid.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::UndefinedVariable`] if the placeholder references an undefined variable.
+    /// - [`ManifestError::Json`] if the substituted manifest is not valid.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn substitute<K, V>(
+        &self,
+        variables: &HashMap<K, V>,
+    ) -> Result<WebAppManifest, ManifestError>
+    where
+        K: Borrow<str> + Hash + Eq,
+        V: AsRef<str>,
+    {
+        let mut value = serde_json::to_value(self)?;
+        substitute_value(&mut value, &|name| variables.get(name).map(|value| value.as_ref()))?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn substitute_value<'a>(
+    value: &mut Value,
+    lookup: &dyn Fn(&str) -> Option<&'a str>,
+) -> Result<(), ManifestError> {
+    match value {
+        Value::String(string) => *string = substitute_str(string, lookup)?,
+        Value::Array(array) => {
+            for item in array {
+                substitute_value(item, lookup)?;
+            }
+        }
+        Value::Object(object) => {
+            for item in object.values_mut() {
+                substitute_value(item, lookup)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Replaces plain and percent-encoded placeholders in the string.
+#[allow(clippy::result_large_err)]
+fn substitute_str<'a>(
+    input: &str,
+    lookup: &dyn Fn(&str) -> Option<&'a str>,
+) -> Result<String, ManifestError> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some((start, open_len)) = find_delimiter(rest, "{{", "%7B%7B") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + open_len..];
+
+        let (end, close_len) = match find_delimiter(after, "}}", "%7D%7D") {
+            Some(end) => end,
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        };
+
+        let name = after[..end].trim();
+        let value = lookup(name)
+            .ok_or_else(|| ManifestError::UndefinedVariable { name: name.to_string() })?;
+        output.push_str(value);
+
+        rest = &after[end + close_len..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Finds the first plain or percent-encoded delimiter and returns its position and length.
+fn find_delimiter(input: &str, plain: &str, encoded: &str) -> Option<(usize, usize)> {
+    let plain = input.find(plain).map(|index| (index, plain.len()));
+    let encoded = input.to_ascii_uppercase().find(encoded).map(|index| (index, encoded.len()));
+
+    match (plain, encoded) {
+        (Some(plain), Some(encoded)) => Some(if plain.0 <= encoded.0 { plain } else { encoded }),
+        (plain, encoded) => plain.or(encoded),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::types::*;
+
+    fn variables() -> HashMap<&'static str, &'static str> {
+        [("version", "1.2.0"), ("base_url", "https://example.com")].iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_substitute_str() {
+        let lookup = |name: &str| variables().get(name).copied();
+        assert_eq!(substitute_str("App {{version}}", &lookup).unwrap(), "App 1.2.0");
+        assert_eq!(substitute_str("{{ base_url }}/{{version}}", &lookup).unwrap(), "https://example.com/1.2.0");
+        assert_eq!(substitute_str("/app/%7b%7Bversion%7D%7D/", &lookup).unwrap(), "/app/1.2.0/");
+        assert_eq!(substitute_str("Unclosed {{version", &lookup).unwrap(), "Unclosed {{version");
+        assert_matches!(substitute_str("{{other}}", &lookup), Err(ManifestError::UndefinedVariable { name }) if name == "other");
+    }
+
+    #[test]
+    fn test_substitute_manifest() {
+        let manifest: WebAppManifest = serde_json::from_str(
+            r#"{
+            "name": "Example {{version}}",
+            "start_url": "{{base_url}}/app/",
+            "icons": [{ "src": "https://cdn.example.com/{{version}}/icon.png" }]
+        }"#,
+        )
+        .unwrap();
+
+        assert_matches!(manifest.start_url, Url::Relative(_));
+
+        let manifest = manifest.substitute(&variables()).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("Example 1.2.0"));
+        assert_eq!(manifest.start_url, Url::Absolute(AbsoluteUrl::parse("https://example.com/app/").unwrap()));
+        assert_eq!(manifest.icons[0].src, Url::Absolute(AbsoluteUrl::parse("https://cdn.example.com/1.2.0/icon.png").unwrap()));
+    }
+}
@@ -0,0 +1,305 @@
+//! Contains Tower middleware for validating uploaded manifests.
+//!
+//! The [`ManifestValidationLayer`] wraps a service that accepts manifest uploads,
+//! such as an endpoint of an app store backend. It collects and parses request bodies
+//! within the configured [`ParseLimits`] and rejects invalid manifests with
+//! [problem details][rfc9457] responses before they reach the inner service. Valid
+//! manifests are passed to the inner service in request extensions as [`ValidatedManifest`].
+//!
+//! Any [`http_body::Body`] is accepted, so the middleware can be used with Axum and Hyper
+//! services. The inner service receives the collected body as [`Full<Bytes>`].
+//!
+//! [rfc9457]: https://www.rfc-editor.org/rfc/rfc9457
+
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http::header::{HeaderValue, CONTENT_TYPE};
+use http::{Request, Response, StatusCode};
+use http_body::Body;
+use http_body_util::{BodyExt, Full, Limited};
+use serde_json::json;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::errors::ManifestError;
+use crate::limits::ParseLimits;
+use crate::validation::{ValidationOptions, ValidationReport};
+use crate::WebAppManifest;
+
+/// The manifest that was parsed and validated by the middleware.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidatedManifest(pub WebAppManifest);
+
+/// A layer that applies [`ManifestValidation`] to services.
+#[derive(Debug, Default, Clone)]
+pub struct ManifestValidationLayer {
+    options: ValidationOptions,
+    limits: ParseLimits,
+}
+
+impl ManifestValidationLayer {
+    /// Creates a layer that validates manifests with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a layer that validates manifests with the provided options.
+    pub fn with_options(options: ValidationOptions) -> Self {
+        Self { options, ..Default::default() }
+    }
+
+    /// Sets the limits that are enforced when parsing manifests.
+    ///
+    /// The [default limits][ParseLimits] are used otherwise.
+    pub fn with_limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl<S> Layer<S> for ManifestValidationLayer {
+    type Service = ManifestValidation<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ManifestValidation { inner, options: self.options.clone(), limits: self.limits.clone() }
+    }
+}
+
+/// A service that parses and validates manifests in request bodies.
+///
+/// Requests are rejected with:
+///
+/// - `415 Unsupported Media Type` if the content type is not a JSON manifest.
+/// - `413 Content Too Large` if the manifest exceeds any of the limits.
+/// - `400 Bad Request` if the body is not a valid manifest document.
+/// - `422 Unprocessable Content` if the manifest has validation errors.
+///
+#[derive(Debug, Clone)]
+pub struct ManifestValidation<S> {
+    inner: S,
+    options: ValidationOptions,
+    limits: ParseLimits,
+}
+
+/// The response future of [`ManifestValidation`].
+pub type ResponseFuture<R, E> = Pin<Box<dyn Future<Output = Result<R, E>> + Send>>;
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ManifestValidation<S>
+where
+    S: Service<Request<Full<Bytes>>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    ReqBody: Body + Send + 'static,
+    ReqBody::Data: Send,
+    ReqBody::Error: Into<Box<dyn Error + Send + Sync>>,
+    ResBody: From<Vec<u8>> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(context)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // The ready service is used for this request and its clone for the next ones
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let options = self.options.clone();
+        let limits = self.limits.clone();
+
+        Box::pin(async move {
+            if !has_manifest_content_type(&request) {
+                let detail = "Manifest must be uploaded as application/manifest+json";
+                let status = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+                return Ok(problem(status, "Unsupported media type", detail, None));
+            }
+
+            let (mut parts, body) = request.into_parts();
+
+            // Stop collecting the body as soon as it exceeds the document size limit
+            let body = match Limited::new(body, limits.max_document_bytes).collect().await {
+                Ok(body) => body.to_bytes(),
+                Err(error) if error.is::<http_body_util::LengthLimitError>() => {
+                    let error = ManifestError::LimitExceeded {
+                        limit: "max_document_bytes",
+                        maximum: limits.max_document_bytes,
+                    };
+                    return Ok(too_large(&error));
+                }
+                Err(error) => {
+                    let detail = error.to_string();
+                    let status = StatusCode::BAD_REQUEST;
+                    return Ok(problem(status, "Invalid request body", &detail, None));
+                }
+            };
+
+            let manifest = match WebAppManifest::from_slice_with_limits(&body, &limits) {
+                Ok(manifest) => manifest,
+                Err(error @ ManifestError::LimitExceeded { .. }) => return Ok(too_large(&error)),
+                Err(error) => {
+                    let detail = error.to_string();
+                    let status = StatusCode::BAD_REQUEST;
+                    return Ok(problem(status, "Invalid manifest document", &detail, None));
+                }
+            };
+
+            let report = manifest.validate_with_options(&options);
+            if !report.is_valid() {
+                let detail = "Manifest has validation errors";
+                let status = StatusCode::UNPROCESSABLE_ENTITY;
+                return Ok(problem(status, "Invalid manifest", detail, Some(&report)));
+            }
+
+            parts.extensions.insert(ValidatedManifest(manifest));
+            inner.call(Request::from_parts(parts, Full::new(body))).await
+        })
+    }
+}
+
+/// Builds the problem details response for the manifest that exceeds the limits.
+fn too_large<B: From<Vec<u8>>>(error: &ManifestError) -> Response<B> {
+    let detail = error.to_string();
+    problem(StatusCode::PAYLOAD_TOO_LARGE, "Manifest too large", &detail, None)
+}
+
+/// Checks whether the request has a JSON manifest content type.
+fn has_manifest_content_type<B>(request: &Request<B>) -> bool {
+    let Some(content_type) = request.headers().get(CONTENT_TYPE) else { return false };
+    let Ok(content_type) = content_type.to_str() else { return false };
+
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    essence == "application/manifest+json" || essence == "application/json"
+}
+
+/// Builds the problem details response.
+fn problem<B: From<Vec<u8>>>(
+    status: StatusCode,
+    title: &str,
+    detail: &str,
+    report: Option<&ValidationReport>,
+) -> Response<B> {
+    let mut body = json!({
+        "type": "about:blank",
+        "title": title,
+        "status": status.as_u16(),
+        "detail": detail,
+    });
+
+    if let Some(report) = report {
+        let errors: Vec<_> = report
+            .errors()
+            .map(|issue| json!({ "path": issue.path, "detail": issue.kind.to_string() }))
+            .collect();
+
+        body["errors"] = json!(errors);
+    }
+
+    let mut response = Response::new(B::from(body.to_string().into_bytes()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/problem+json"));
+    response
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use std::convert::Infallible;
+    use std::future::{ready, Ready};
+    use std::task::Waker;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Echo;
+
+    impl Service<Request<Full<Bytes>>> for Echo {
+        type Response = Response<Vec<u8>>;
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: Request<Full<Bytes>>) -> Self::Future {
+            let manifest = request.extensions().get::<ValidatedManifest>().unwrap();
+            ready(Ok(Response::new(manifest.0.name.clone().unwrap_or_default().into_bytes())))
+        }
+    }
+
+    fn call(content_type: &str, body: &str) -> Response<Vec<u8>> {
+        call_with_limits(content_type, body, ParseLimits::default())
+    }
+
+    fn call_with_limits(content_type: &str, body: &str, limits: ParseLimits) -> Response<Vec<u8>> {
+        let mut service = ManifestValidationLayer::new().with_limits(limits).layer(Echo);
+
+        let request = Request::builder()
+            .header(CONTENT_TYPE, content_type)
+            .body(Full::new(Bytes::from(body.to_string())))
+            .unwrap();
+
+        let mut future = Box::pin(service.call(request));
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(Ok(response)) => response,
+            _ => unreachable!(),
+        }
+    }
+
+    fn body(response: &Response<Vec<u8>>) -> serde_json::Value {
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn test_valid_manifest() {
+        let response = call("application/manifest+json; charset=utf-8", r#"{"name":"Example"}"#);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), b"Example");
+    }
+
+    #[test]
+    fn test_unsupported_media_type() {
+        let response = call("text/plain", r#"{"name":"Example"}"#);
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/problem+json");
+        assert_eq!(body(&response)["status"], 415);
+    }
+
+    #[test]
+    fn test_invalid_document() {
+        let response = call("application/json", "{");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body(&response)["title"], "Invalid manifest document");
+    }
+
+    #[test]
+    fn test_invalid_manifest() {
+        let manifest = r#"{"file_handlers":[{"action":"/open","accept":{"invalid":[".csv"]}}]}"#;
+        let response = call("application/manifest+json", manifest);
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = body(&response);
+        assert_eq!(body["errors"][0]["path"], "file_handlers[0].accept");
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = ParseLimits { max_document_bytes: 8, ..Default::default() };
+        let response = call_with_limits("application/json", r#"{"name":"Example"}"#, limits);
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body(&response)["title"], "Manifest too large");
+
+        let limits = ParseLimits { max_string_bytes: 4, ..Default::default() };
+        let response = call_with_limits("application/json", r#"{"name":"Example"}"#, limits);
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
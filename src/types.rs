@@ -1,17 +1,22 @@
 //! Contains all manifest enums.
 
-use std::convert::TryInto;
+use std::cmp::Ordering;
+use std::convert::{Infallible, TryFrom, TryInto};
 use std::error::Error;
+use std::fmt;
 use std::str::FromStr;
 
 use parse_display::{Display, FromStr};
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 use crate::errors::ManifestError;
 
 #[rustfmt::skip] #[doc(no_inline)] pub use csscolorparser::Color;
 #[rustfmt::skip] #[doc(no_inline)] pub use language_tags::LanguageTag;
 #[rustfmt::skip] #[doc(no_inline)] pub use mime::MediaRange;
+#[rustfmt::skip] #[doc(no_inline)] pub use mime::MediaType;
 #[rustfmt::skip] #[doc(no_inline)] pub use url::Url as AbsoluteUrl;
 #[rustfmt::skip] #[doc(no_inline)] pub use String as RelativeUrl;
 
@@ -21,7 +26,10 @@ use crate::errors::ManifestError;
 /// as a string. All relative URLs and the document URL in the manifest
 /// can be converted to absolute URLs and parsed by calling
 /// [`process`][crate::WebAppManifest::process].
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Hash)]
+///
+/// When deserializing, strings that can be parsed as absolute URLs are stored
+/// as absolute URLs, other strings as relative URLs, and `null` as unknown URL.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Hash)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Url {
@@ -39,6 +47,9 @@ pub enum Url {
     /// URL as a base. In most other cases, it represents am invalid URL
     /// that should not be provided, and attempting to parse/use it should
     /// cause an error.
+    ///
+    /// Manifest fields with unknown URLs are omitted when serializing the
+    /// manifest, because browsers treat `null` as an invalid URL string.
     Unknown,
 }
 
@@ -61,6 +72,51 @@ impl FromStr for Url {
     }
 }
 
+impl<'de> Deserialize<'de> for Url {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UrlVisitor;
+
+        impl<'de> Visitor<'de> for UrlVisitor {
+            type Value = Url;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a URL string or null")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Url::from_str(value).map_err(E::custom)
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(Url::Unknown)
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(Url::Unknown)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        deserializer.deserialize_any(UrlVisitor)
+    }
+}
+
+impl fmt::Display for Url {
+    /// Formats the URL as the serialized absolute URL or the unparsed relative URL.
+    ///
+    /// Unknown URLs are formatted as `<unknown>`, which is not a valid URL, so the
+    /// output should only be used for display purposes, such as in errors and logs.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str().unwrap_or("<unknown>"))
+    }
+}
+
 impl TryInto<String> for Url {
     type Error = ManifestError;
 
@@ -84,8 +140,327 @@ impl TryInto<AbsoluteUrl> for Url {
     }
 }
 
+impl Url {
+    /// Returns the URL as a string.
+    ///
+    /// Returns the serialized URL for absolute URLs, the unparsed string for relative
+    /// URLs and `None` for unknown URLs.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Absolute(url) => Some(url.as_str()),
+            Self::Relative(url) => Some(url),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Returns `true` if the URL is absolute.
+    pub fn is_absolute(&self) -> bool {
+        matches!(self, Self::Absolute(_))
+    }
+
+    /// Returns `true` if the URL is relative.
+    pub fn is_relative(&self) -> bool {
+        matches!(self, Self::Relative(_))
+    }
+
+    /// Returns `true` if the URL is unknown.
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown)
+    }
+
+    /// Resolves the URL with the provided base URL.
+    ///
+    /// Relative URLs are parsed with the base URL, while absolute URLs are returned
+    /// unchanged. This is the same resolution that is performed for most URLs when
+    /// [processing][crate::WebAppManifest::process] the manifest.
+    ///
+    /// # Parameters
+    ///
+    /// - `base`: The base URL, usually the manifest URL.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok`: The resolved absolute URL.
+    /// - `Err`: An error in case the URL cannot be resolved.
+    ///
+    /// # Errors
+    ///
+    /// - [`ManifestError::UrlParsing`] if the relative URL cannot be parsed.
+    /// - [`ManifestError::InvalidUnknownUrl`] if the URL is unknown.
+    ///
+    #[allow(clippy::result_large_err)]
+    pub fn join(&self, base: &AbsoluteUrl) -> Result<AbsoluteUrl, ManifestError> {
+        match self {
+            Self::Absolute(url) => Ok(url.clone()),
+            Self::Relative(url) => Ok(base.join(url)?),
+            Self::Unknown => Err(ManifestError::InvalidUnknownUrl),
+        }
+    }
+
+    /// Returns the host of the absolute URL in its ASCII form, with internationalized
+    /// domain names encoded using punycode.
+    ///
+    /// Returns `None` for relative and unknown URLs and URLs without a host.
+    pub fn ascii_host(&self) -> Option<&str> {
+        match self {
+            Self::Absolute(url) => url.host_str(),
+            _ => None,
+        }
+    }
+
+    /// Returns the host of the absolute URL in its Unicode form, with punycode-encoded
+    /// labels decoded.
+    ///
+    /// The Unicode form may contain characters that are visually confusable with other
+    /// characters. Use [`display_host`][Url::display_host] when showing the host to users.
+    ///
+    /// Returns `None` for relative and unknown URLs and URLs without a host.
+    pub fn unicode_host(&self) -> Option<String> {
+        let (host, _) = idna::domain_to_unicode(self.ascii_host()?);
+        Some(host)
+    }
+
+    /// Returns the host of the absolute URL in the form that is safe to display to users.
+    ///
+    /// Similarly to browsers, the Unicode form is returned unless the host is potentially
+    /// confusable (for example, when it mixes Latin and Cyrillic characters), in which case
+    /// the ASCII form is returned.
+    ///
+    /// Returns `None` for relative and unknown URLs and URLs without a host.
+    pub fn display_host(&self) -> Option<String> {
+        let host = self.unicode_host()?;
+
+        if is_confusable_host(&host) {
+            self.ascii_host().map(str::to_string)
+        } else {
+            Some(host)
+        }
+    }
+}
+
+/// The script of a character, as relevant for confusable detection.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Script {
+    Common,
+    Latin,
+    Greek,
+    Cyrillic,
+    Other,
+}
+
+impl Script {
+    fn of(character: char) -> Self {
+        match character {
+            '0'..='9' | '-' | '_' => Self::Common,
+            'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => {
+                Self::Latin
+            }
+            '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Self::Greek,
+            '\u{0400}'..='\u{052F}' => Self::Cyrillic,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Characters of Greek and Cyrillic scripts that look like Latin characters.
+pub(crate) const LOOKALIKES: &str = "асеһіјӏорԛѕԝхуԁοαιυνρ";
+
+/// Checks whether the Unicode host is potentially confusable with another host.
+///
+/// A host is considered confusable if any of its labels mixes Latin characters with
+/// Greek or Cyrillic characters, or if the label consists only of Greek or Cyrillic
+/// characters that look like Latin characters (such as `аррӏе`).
+pub(crate) fn is_confusable_host(host: &str) -> bool {
+    host.split('.').any(is_confusable_word)
+}
+
+/// Checks whether the word (a host label or a word of a name) is potentially confusable.
+///
+/// See [`is_confusable_host`] for the rules.
+pub(crate) fn is_confusable_word(word: &str) -> bool {
+    if word.is_ascii() {
+        return false;
+    }
+
+    let scripts: Vec<Script> =
+        word.chars().map(Script::of).filter(|script| *script != Script::Common).collect();
+
+    let has_latin = scripts.contains(&Script::Latin);
+    let has_lookalike_script =
+        scripts.iter().any(|script| matches!(script, Script::Greek | Script::Cyrillic));
+
+    let mixed = has_latin && has_lookalike_script;
+    let whole_lookalike = !has_latin
+        && has_lookalike_script
+        && word
+            .chars()
+            .filter(|character| Script::of(*character) != Script::Common)
+            .all(|character| LOOKALIKES.contains(character));
+
+    mixed || whole_lookalike
+}
+
+/// The algorithm used to check whether URLs are within the application scope.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum ScopeMatching {
+    /// The URL path must match the scope path on path segment boundaries, so the
+    /// scope `/app` matches `/app` and `/app/page`, but not `/application`.
+    ///
+    /// This is the default variant and matches the behavior of browsers.
+    Segments,
+
+    /// The URL path must only start with the scope path, so the scope `/app` also
+    /// matches `/application`.
+    ///
+    /// This was the behavior of earlier versions of this crate.
+    Prefix,
+}
+
+impl ScopeMatching {
+    /// Checks whether the URL path is within the scope path.
+    pub fn matches_path(self, path: &str, scope: &str) -> bool {
+        match self {
+            Self::Prefix => path.starts_with(scope),
+            Self::Segments => match path.strip_prefix(scope) {
+                Some(rest) => rest.is_empty() || scope.ends_with('/') || rest.starts_with('/'),
+                None => false,
+            },
+        }
+    }
+}
+
+impl Default for ScopeMatching {
+    #[inline]
+    fn default() -> Self {
+        Self::Segments
+    }
+}
+
+/// The policy used to check whether the start URL is in the same origin as the document URL.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum StartUrlOriginPolicy {
+    /// The start URL must have exactly the same origin as the document URL.
+    ///
+    /// This is the default variant and matches the specification.
+    Exact,
+
+    /// The start URL must have the same scheme and registrable domain as the document URL,
+    /// so `https://app.example.com` is accepted for the document on `https://www.example.com`.
+    /// Hosts without a registrable domain (such as IP addresses) must match exactly.
+    ///
+    /// Registrable domains are determined using the Public Suffix List, which requires
+    /// the `psl` feature. Without it, this policy behaves like [`Exact`][Self::Exact].
+    RegistrableDomain,
+
+    /// The origin of the start URL is not checked. This is useful for crawlers that
+    /// do not have access to the real document URL.
+    Disabled,
+}
+
+impl StartUrlOriginPolicy {
+    /// Checks whether the start URL has an origin permitted by the policy.
+    pub fn matches(self, start_url: &AbsoluteUrl, document_url: &AbsoluteUrl) -> bool {
+        match self {
+            Self::Exact => crate::is_same_origin(start_url, document_url),
+            #[cfg(feature = "psl")]
+            Self::RegistrableDomain => {
+                // IP addresses and other non-domain hosts do not have a registrable domain
+                let registrable_domain = |url: &AbsoluteUrl| match url.host()? {
+                    url::Host::Domain(host) => {
+                        Some(psl::domain_str(host).unwrap_or(host).to_string())
+                    }
+                    host => Some(host.to_string()),
+                };
+
+                start_url.scheme() == document_url.scheme()
+                    && registrable_domain(start_url) == registrable_domain(document_url)
+            }
+            #[cfg(not(feature = "psl"))]
+            Self::RegistrableDomain => crate::is_same_origin(start_url, document_url),
+            Self::Disabled => true,
+        }
+    }
+}
+
+impl Default for StartUrlOriginPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Exact
+    }
+}
+
+/// The policy for resources with URLs that are not within the application scope.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum OutOfScopePolicy {
+    /// Processing fails with [`ManifestError::NotWithinScope`].
+    ///
+    /// This is the default variant. It is useful for linters that should report all
+    /// problems to the manifest author.
+    Error,
+
+    /// The resource is removed from the manifest, similarly to how browsers ignore it.
+    /// Optional URLs, such as the new tab button URL, are removed instead of the resource.
+    Drop,
+}
+
+impl Default for OutOfScopePolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Error
+    }
+}
+
+/// The snapshot of the specification that determines which members are recognized.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum SpecVersion {
+    /// Only members of the core [Web Application Manifest](https://www.w3.org/TR/appmanifest/)
+    /// specification are recognized. This is useful for conservative consumers that do not
+    /// want to support experimental members.
+    Recommendation,
+
+    /// All members of the living editor's draft, its companion specifications and
+    /// incubations supported by this crate are recognized.
+    ///
+    /// This is the default variant.
+    EditorsDraft,
+}
+
+impl SpecVersion {
+    /// The members recognized by [`SpecVersion::Recommendation`].
+    pub const RECOMMENDATION_MEMBERS: &'static [&'static str] = &[
+        "id",
+        "start_url",
+        "scope",
+        "name",
+        "short_name",
+        "dir",
+        "lang",
+        "display",
+        "orientation",
+        "background_color",
+        "theme_color",
+        "shortcuts",
+        "icons",
+    ];
+
+    /// Checks whether the member is recognized by the specification snapshot.
+    pub fn recognizes(self, member: &str) -> bool {
+        match self {
+            Self::Recommendation => Self::RECOMMENDATION_MEMBERS.contains(&member),
+            Self::EditorsDraft => true,
+        }
+    }
+}
+
+impl Default for SpecVersion {
+    #[inline]
+    fn default() -> Self {
+        Self::EditorsDraft
+    }
+}
+
 /// The base direction in which to display direction-capable members of the manifest.
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Hash)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum Direction {
@@ -98,177 +473,1250 @@ pub enum Direction {
     /// of [Unicode Bidirectional Algorithm](https://www.unicode.org/reports/tr9/tr9-42.html).
     Auto,
 
-    /// Left-to-right text.
-    ///
-    /// The implementation should override the [Rule P3](https://www.unicode.org/reports/tr9/tr9-42.html#P3)
-    /// of [Unicode Bidirectional Algorithm](https://www.unicode.org/reports/tr9/tr9-42.html),
-    /// setting the paragraph embedding level to `0`.
-    Ltr,
+    /// Left-to-right text.
+    ///
+    /// The implementation should override the [Rule P3](https://www.unicode.org/reports/tr9/tr9-42.html#P3)
+    /// of [Unicode Bidirectional Algorithm](https://www.unicode.org/reports/tr9/tr9-42.html),
+    /// setting the paragraph embedding level to `0`.
+    Ltr,
+
+    /// Right-to-left text.
+    ///
+    /// The implementation should override the [Rule P3](https://www.unicode.org/reports/tr9/tr9-42.html#P3)
+    /// of [Unicode Bidirectional Algorithm](https://www.unicode.org/reports/tr9/tr9-42.html),
+    /// setting the paragraph embedding level to `1`.
+    Rtl,
+
+    /// Directionality that is not known to this crate.
+    ///
+    /// User agents ignore unknown values and use the `auto` directionality instead.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Direction {
+    /// Returns the keyword of the directionality, as it is used in the manifest.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Auto => "auto",
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+            Self::Unknown(other) => other,
+        }
+    }
+
+    /// Converts the keyword to the directionality, ignoring the ASCII case.
+    fn from_keyword(keyword: &str) -> Self {
+        match keyword.to_ascii_lowercase().as_str() {
+            "auto" => Self::Auto,
+            "ltr" => Self::Ltr,
+            "rtl" => Self::Rtl,
+            _ => Self::Unknown(keyword.to_string()),
+        }
+    }
+}
+
+impl Default for Direction {
+    #[inline]
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl<'de> Deserialize<'de> for Direction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_keyword(&String::deserialize(deserializer)?))
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Infallible;
+
+    /// Parses the directionality from the keyword, ignoring the ASCII case. Keywords that
+    /// are not known are preserved as the `Unknown` variant.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_keyword(string))
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// The preferred display mode of the web application.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Display {
+    /// Opens the web application in a conventional browser tab or new window,
+    /// depending on the browser and platform.
+    ///
+    /// This is the default variant and must be supported by the user agent.
+    Browser,
+
+    /// Opens the web application with browser UI elements hidden and takes
+    /// up the entirety of the available display area.
+    ///
+    /// If not supported, the user agent must fall back to the `standalone` mode.
+    Fullscreen,
+
+    /// Opens the web application to look and feel like a standalone native
+    /// application. This can include the application having a different window,
+    /// its own icon in the application launcher, etc. In this mode, the user agent
+    /// will exclude standard browser UI elements such as an URL bar, but can
+    /// include other system UI elements such as a status bar and/or system
+    /// back button.
+    ///
+    /// If not supported, the user agent must fall back to the `minimal-ui` mode.
+    Standalone,
+
+    /// Opens the web application to look and feel like a standalone native
+    /// application, but provides the end-user with some means to access a minimal
+    /// set of UI elements for controlling navigation. A user agent can include
+    /// other platform specific UI elements, such as "share" and "print" buttons or
+    /// whatever is customary on the platform and user agent.
+    ///
+    /// If not supported, the user agent must fall back to the `browser` mode.
+    MinimalUi,
+
+    /// Display mode that is not known to this crate.
+    ///
+    /// User agents ignore unknown values and use the `browser` mode instead.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Display {
+    /// Returns the keyword of the display mode, as it is used in the manifest.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Browser => "browser",
+            Self::Fullscreen => "fullscreen",
+            Self::Standalone => "standalone",
+            Self::MinimalUi => "minimal-ui",
+            Self::Unknown(other) => other,
+        }
+    }
+
+    /// Converts the keyword to the display mode, ignoring the ASCII case.
+    fn from_keyword(keyword: &str) -> Self {
+        match keyword.to_ascii_lowercase().as_str() {
+            "browser" => Self::Browser,
+            "fullscreen" => Self::Fullscreen,
+            "standalone" => Self::Standalone,
+            "minimal-ui" => Self::MinimalUi,
+            _ => Self::Unknown(keyword.to_string()),
+        }
+    }
+}
+
+impl Default for Display {
+    #[inline]
+    fn default() -> Self {
+        Self::Browser
+    }
+}
+
+impl<'de> Deserialize<'de> for Display {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_keyword(&String::deserialize(deserializer)?))
+    }
+}
+
+impl FromStr for Display {
+    type Err = Infallible;
+
+    /// Parses the display mode from the keyword, ignoring the ASCII case. Keywords that
+    /// are not known are preserved as the `Unknown` variant.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_keyword(string))
+    }
+}
+
+impl fmt::Display for Display {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// A display mode in the [`display_override`][crate::WebAppManifest::display_override] sequence.
+///
+/// In addition to the base [`Display`] modes, this includes extended display modes that
+/// can only be requested through the `display_override` member. Display modes that are
+/// not known to this crate are preserved as [`DisplayOverride::Unknown`] so they are
+/// not lost when the manifest is serialized again.
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Eq, PartialEq, Clone, Hash)]
+pub enum DisplayOverride {
+    /// The `browser` display mode.
+    ///
+    /// See [`Display::Browser`].
+    Browser,
+
+    /// The `fullscreen` display mode.
+    ///
+    /// See [`Display::Fullscreen`].
+    Fullscreen,
+
+    /// The `standalone` display mode.
+    ///
+    /// See [`Display::Standalone`].
+    Standalone,
+
+    /// The `minimal-ui` display mode.
+    ///
+    /// See [`Display::MinimalUi`].
+    MinimalUi,
+
+    /// Opens the web application like the `standalone` mode, but replaces the
+    /// title bar with an overlay of window controls, allowing the application
+    /// to draw its content into the title bar area.
+    WindowControlsOverlay,
+
+    /// Opens the web application in a standalone window that can contain
+    /// multiple application tabs.
+    Tabbed,
+
+    /// Opens the web application in a standalone window without any browser
+    /// or title bar UI elements.
+    Borderless,
+
+    /// Display mode that is not known to this crate.
+    Unknown(String),
+}
+
+impl FromStr for DisplayOverride {
+    type Err = Infallible;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(match string {
+            "browser" => Self::Browser,
+            "fullscreen" => Self::Fullscreen,
+            "standalone" => Self::Standalone,
+            "minimal-ui" => Self::MinimalUi,
+            "window-controls-overlay" => Self::WindowControlsOverlay,
+            "tabbed" => Self::Tabbed,
+            "borderless" => Self::Borderless,
+            other => Self::Unknown(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for DisplayOverride {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Browser => "browser",
+            Self::Fullscreen => "fullscreen",
+            Self::Standalone => "standalone",
+            Self::MinimalUi => "minimal-ui",
+            Self::WindowControlsOverlay => "window-controls-overlay",
+            Self::Tabbed => "tabbed",
+            Self::Borderless => "borderless",
+            Self::Unknown(other) => other,
+        })
+    }
+}
+
+impl From<Display> for DisplayOverride {
+    fn from(display: Display) -> Self {
+        match display {
+            Display::Browser => Self::Browser,
+            Display::Fullscreen => Self::Fullscreen,
+            Display::Standalone => Self::Standalone,
+            Display::MinimalUi => Self::MinimalUi,
+            Display::Unknown(other) => Self::Unknown(other),
+        }
+    }
+}
+
+impl TryFrom<DisplayOverride> for Display {
+    type Error = DisplayOverride;
+
+    /// Converts the display mode into one of the base display modes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the original display mode if it is one of the extended display modes.
+    ///
+    fn try_from(display: DisplayOverride) -> Result<Self, Self::Error> {
+        match display {
+            DisplayOverride::Browser => Ok(Self::Browser),
+            DisplayOverride::Fullscreen => Ok(Self::Fullscreen),
+            DisplayOverride::Standalone => Ok(Self::Standalone),
+            DisplayOverride::MinimalUi => Ok(Self::MinimalUi),
+            DisplayOverride::Unknown(other) => Ok(Self::Unknown(other)),
+            other => Err(other),
+        }
+    }
+}
+
+/// An entry in the allowlist of a [`permissions_policy`][crate::WebAppManifest::permissions_policy]
+/// feature, which specifies the origins that are allowed to use the feature.
+///
+/// Keywords may be specified either with or without single quotes (such as `self` or `'self'`).
+/// Entries that are not known to this crate are preserved as [`AllowlistEntry::Unknown`].
+///
+/// # See also
+///
+/// - [Permissions Policy](https://w3c.github.io/webappsec-permissions-policy/#allowlists)
+///
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Eq, PartialEq, Clone, Hash)]
+pub enum AllowlistEntry {
+    /// The `*` entry, which allows the feature for all origins.
+    Any,
+
+    /// The `self` keyword, which allows the feature for the origin of the application.
+    SelfOrigin,
+
+    /// The `src` keyword, which allows the feature for the origin of the embedded content.
+    Src,
+
+    /// The `none` keyword, which does not allow the feature for any origin.
+    None,
+
+    /// An origin that is allowed to use the feature.
+    Origin(AbsoluteUrl),
+
+    /// Entry that is not known to this crate.
+    Unknown(String),
+}
+
+impl AllowlistEntry {
+    /// Checks whether the entry allows the feature for the provided origin.
+    ///
+    /// # Parameters
+    ///
+    /// - `origin`: The URL whose origin is checked.
+    /// - `self_origin`: The URL of the application, used to resolve the `self` keyword.
+    ///
+    pub fn matches(&self, origin: &AbsoluteUrl, self_origin: &AbsoluteUrl) -> bool {
+        match self {
+            Self::Any => true,
+            Self::SelfOrigin => crate::is_same_origin(origin, self_origin),
+            Self::Origin(allowed) => crate::is_same_origin(origin, allowed),
+            Self::Src | Self::None | Self::Unknown(_) => false,
+        }
+    }
+}
+
+impl FromStr for AllowlistEntry {
+    type Err = Infallible;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let keyword =
+            string.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')).unwrap_or(string);
+
+        Ok(match keyword.to_ascii_lowercase().as_str() {
+            "*" => Self::Any,
+            "self" => Self::SelfOrigin,
+            "src" => Self::Src,
+            "none" => Self::None,
+            _ => match AbsoluteUrl::parse(string) {
+                Ok(url) if url.origin().is_tuple() => Self::Origin(url),
+                _ => Self::Unknown(string.to_string()),
+            },
+        })
+    }
+}
+
+impl fmt::Display for AllowlistEntry {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => formatter.write_str("*"),
+            Self::SelfOrigin => formatter.write_str("self"),
+            Self::Src => formatter.write_str("src"),
+            Self::None => formatter.write_str("none"),
+            Self::Origin(url) => formatter.write_str(&url.origin().ascii_serialization()),
+            Self::Unknown(other) => formatter.write_str(other),
+        }
+    }
+}
+
+/// The preferred orientation of the web application.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Orientation {
+    /// Any is an orientation that means the screen can be locked to any one
+    /// of the `portrait-primary`, `portrait-secondary`, `landscape-primary`
+    /// and `landscape-secondary`.
+    ///
+    /// This is the default variant.
+    Any,
+
+    /// Natural is an orientation that refers to either `portrait-primary` or
+    /// `landscape-primary` depending on the device's usual orientation. This
+    /// orientation is usually provided by the underlying operating system.
+    Natural,
+
+    /// Landscape is an orientation where the screen width is greater than the
+    /// screen height.
+    Landscape,
+
+    /// Portrait is an orientation where the screen width is less than or equal
+    /// to the screen height.
+    Portrait,
+
+    /// Landscape-primary is an orientation where the screen width is greater than
+    /// the screen height. If the device's natural orientation is landscape, then it
+    /// is in landscape-primary when held in that position.
+    LandscapePrimary,
+
+    /// Landscape-secondary is an orientation where the screen width is greater than
+    /// the screen height. If the device's natural orientation is landscape, then it
+    /// is in landscape-secondary when rotated 180° from its natural orientation.
+    LandscapeSecondary,
+
+    /// Portrait-primary is an orientation where the screen width is less than or equal
+    /// to the screen height. If the device's natural orientation is portrait, then it
+    /// is in portrait-primary when held in that position.
+    PortraitPrimary,
+
+    /// Portrait-secondary is an orientation where the screen width is less than or equal
+    /// to the screen height. If the device's natural orientation is portrait, then it
+    /// is in portrait-secondary when rotated 180° from its natural orientation.
+    PortraitSecondary,
+
+    /// Orientation that is not known to this crate.
+    ///
+    /// User agents ignore unknown values and use the `any` orientation instead.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Orientation {
+    /// Returns the keyword of the orientation, as it is used in the manifest.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Any => "any",
+            Self::Natural => "natural",
+            Self::Landscape => "landscape",
+            Self::Portrait => "portrait",
+            Self::LandscapePrimary => "landscape-primary",
+            Self::LandscapeSecondary => "landscape-secondary",
+            Self::PortraitPrimary => "portrait-primary",
+            Self::PortraitSecondary => "portrait-secondary",
+            Self::Unknown(other) => other,
+        }
+    }
+
+    /// Converts the keyword to the orientation, ignoring the ASCII case.
+    fn from_keyword(keyword: &str) -> Self {
+        match keyword.to_ascii_lowercase().as_str() {
+            "any" => Self::Any,
+            "natural" => Self::Natural,
+            "landscape" => Self::Landscape,
+            "portrait" => Self::Portrait,
+            "landscape-primary" => Self::LandscapePrimary,
+            "landscape-secondary" => Self::LandscapeSecondary,
+            "portrait-primary" => Self::PortraitPrimary,
+            "portrait-secondary" => Self::PortraitSecondary,
+            _ => Self::Unknown(keyword.to_string()),
+        }
+    }
+}
+
+impl Default for Orientation {
+    #[inline]
+    fn default() -> Self {
+        Self::Any
+    }
+}
+
+impl<'de> Deserialize<'de> for Orientation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_keyword(&String::deserialize(deserializer)?))
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = Infallible;
+
+    /// Parses the orientation from the keyword, ignoring the ASCII case. Keywords that
+    /// are not known are preserved as the `Unknown` variant.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_keyword(string))
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// The HTTP request method for the web share target.
+#[derive(Display, FromStr, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema), schemars(rename_all = "UPPERCASE"))]
+#[display(style = "UPPERCASE")]
+pub enum ShareTargetMethod {
+    /// The web share target uses the GET method.
+    ///
+    /// This is the default variant.
+    #[from_str(regex = "(?i)GET")]
+    Get,
+
+    /// The web share target uses the POST method.
+    #[from_str(regex = "(?i)POST")]
+    Post,
+}
+
+impl Default for ShareTargetMethod {
+    #[inline]
+    fn default() -> Self {
+        Self::Get
+    }
+}
+
+/// The encoding in the body of a POST request for the web share target.
+/// It is ignored when the method is GET.
+#[derive(Display, FromStr, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ShareTargetEnctype {
+    /// The web share target uses `application/x-www-form-urlencoded` encoding.
+    ///
+    /// This is the default variant.
+    #[display("application/x-www-form-urlencoded")]
+    #[from_str(regex = "(?i)application/x-www-form-urlencoded")]
+    #[cfg_attr(feature = "schemars", schemars(rename = "application/x-www-form-urlencoded"))]
+    UrlEncoded,
+
+    /// The web share target uses `multipart/form-data` encoding.
+    #[display("multipart/form-data")]
+    #[from_str(regex = "(?i)multipart/form-data")]
+    #[cfg_attr(feature = "schemars", schemars(rename = "multipart/form-data"))]
+    FormData,
+}
+
+impl Default for ShareTargetEnctype {
+    #[inline]
+    fn default() -> Self {
+        Self::UrlEncoded
+    }
+}
+
+/// The client mode of the launch handler, which determines whether the web application
+/// should be launched in a new or an existing application client.
+#[derive(Display, FromStr, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema), schemars(rename_all = "kebab-case"))]
+#[display(style = "kebab-case")]
+pub enum ClientMode {
+    /// The user agent decides what works best for the platform.
+    ///
+    /// This is the default variant.
+    Auto,
+
+    /// A new application client is created to load the launch target URL.
+    NavigateNew,
+
+    /// The most recently interacted with application client is navigated to
+    /// the launch target URL, if one is available.
+    NavigateExisting,
+
+    /// The most recently interacted with application client is focused, if one
+    /// is available, but it is not navigated to the launch target URL.
+    FocusExisting,
+}
+
+impl Default for ClientMode {
+    #[inline]
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+/// The client modes of the launch handler, as they were specified in the manifest.
+///
+/// The manifest may specify either a single client mode or an ordered list of client
+/// modes, from which the first supported one is used. The original form is preserved,
+/// so the value is serialized back the same way as it was specified.
+///
+/// Values are stored as strings, so unsupported values are preserved. Use the
+/// [`resolve`][ClientModes::resolve] method to get the client mode that should be used.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum ClientModes {
+    /// A single client mode.
+    Single(String),
+
+    /// An ordered list of client modes.
+    ///
+    /// This is the default variant.
+    List(Vec<String>),
+}
+
+impl ClientModes {
+    /// Returns the specified client modes as a slice, in order of preference.
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            Self::Single(mode) => std::slice::from_ref(mode),
+            Self::List(modes) => modes,
+        }
+    }
+
+    /// Returns an iterator over the specified client modes, in order of preference.
+    pub fn iter(&self) -> std::slice::Iter<'_, String> {
+        self.as_slice().iter()
+    }
+
+    /// Returns the number of the specified client modes.
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns `true` if no client modes are specified.
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Returns the first supported client mode, or [`ClientMode::Auto`] if there
+    /// is no supported value.
+    pub fn resolve(&self) -> ClientMode {
+        self.iter().find_map(|mode| mode.parse().ok()).unwrap_or_default()
+    }
+}
+
+impl Default for ClientModes {
+    #[inline]
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+
+impl From<ClientMode> for ClientModes {
+    fn from(mode: ClientMode) -> Self {
+        Self::Single(mode.to_string())
+    }
+}
+
+impl From<Vec<ClientMode>> for ClientModes {
+    fn from(modes: Vec<ClientMode>) -> Self {
+        Self::List(modes.iter().map(ToString::to_string).collect())
+    }
+}
+
+/// How the web application should be launched when multiple files are opened at once.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum FileHandlerLaunchType {
+    /// All files are opened in a single application client.
+    ///
+    /// This is the default variant.
+    SingleClient,
+
+    /// Each file is opened in a separate application client.
+    MultipleClients,
+}
+
+impl Default for FileHandlerLaunchType {
+    #[inline]
+    fn default() -> Self {
+        Self::SingleClient
+    }
+}
+
+/// The legacy link capturing behavior of the web application.
+///
+/// *Note:* This has been superseded by the [`launch_handler`][crate::WebAppManifest::launch_handler]
+/// member. Use [`ClientMode::try_from`] to get the equivalent client mode.
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Eq, PartialEq, Clone, Hash)]
+pub enum CaptureLinks {
+    /// Links are not captured and are opened in the browser.
+    None,
+
+    /// Links are captured and opened in a new application client.
+    NewClient,
+
+    /// Links are captured and navigate an existing application client.
+    ExistingClientNavigate,
+
+    /// Links are captured and an existing application client is focused, with the
+    /// link being delivered to it as an event.
+    ExistingClientEvent,
+
+    /// Behavior that is not known to this crate.
+    Unknown(String),
+}
+
+impl FromStr for CaptureLinks {
+    type Err = Infallible;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(match string {
+            "none" => Self::None,
+            "new-client" => Self::NewClient,
+            "existing-client-navigate" => Self::ExistingClientNavigate,
+            "existing-client-event" => Self::ExistingClientEvent,
+            other => Self::Unknown(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for CaptureLinks {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::None => "none",
+            Self::NewClient => "new-client",
+            Self::ExistingClientNavigate => "existing-client-navigate",
+            Self::ExistingClientEvent => "existing-client-event",
+            Self::Unknown(other) => other,
+        })
+    }
+}
+
+impl TryFrom<&CaptureLinks> for ClientMode {
+    type Error = ();
+
+    /// Converts the link capturing behavior into the equivalent launch handler client mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for [`CaptureLinks::None`] and unknown behaviors, which do not
+    /// have an equivalent client mode.
+    ///
+    fn try_from(capture_links: &CaptureLinks) -> Result<Self, Self::Error> {
+        match capture_links {
+            CaptureLinks::NewClient => Ok(Self::NavigateNew),
+            CaptureLinks::ExistingClientNavigate => Ok(Self::NavigateExisting),
+            CaptureLinks::ExistingClientEvent => Ok(Self::FocusExisting),
+            CaptureLinks::None | CaptureLinks::Unknown(_) => Err(()),
+        }
+    }
+}
+
+/// The type of the service worker script.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum WorkerType {
+    /// The script is a classic script.
+    ///
+    /// This is the default variant.
+    Classic,
+
+    /// The script is a JavaScript module.
+    Module,
+}
+
+impl Default for WorkerType {
+    #[inline]
+    fn default() -> Self {
+        Self::Classic
+    }
+}
+
+/// Which service worker resources are checked in the HTTP cache when updating the service worker.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateViaCache {
+    /// The HTTP cache is used for imported scripts, but not for the main script.
+    ///
+    /// This is the default variant.
+    Imports,
+
+    /// The HTTP cache is used for the main script and imported scripts.
+    All,
+
+    /// The HTTP cache is not used for any scripts.
+    None,
+}
+
+impl Default for UpdateViaCache {
+    #[inline]
+    fn default() -> Self {
+        Self::Imports
+    }
+}
+
+/// The distribution platform of a related application or a screenshot.
+///
+/// Known values are taken from the [registry of platform values](https://github.com/w3c/manifest/wiki/Platforms),
+/// while unknown values are preserved as [`Other`][Platform::Other].
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Eq, PartialEq, Clone, Hash)]
+pub enum Platform {
+    /// Google Play Store.
+    Play,
+
+    /// Apple App Store.
+    Itunes,
+
+    /// Microsoft Store.
+    Windows,
+
+    /// F-Droid.
+    FDroid,
+
+    /// Amazon Appstore.
+    Amazon,
+
+    /// Chrome Web Store.
+    ChromeWebStore,
+
+    /// Google Play Store on ChromeOS.
+    ChromeOsPlay,
+
+    /// Another web application.
+    Webapp,
+
+    /// Platform that is not known to this crate.
+    Other(String),
+}
+
+impl FromStr for Platform {
+    type Err = Infallible;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(match string {
+            "play" => Self::Play,
+            "itunes" => Self::Itunes,
+            "windows" => Self::Windows,
+            "f-droid" => Self::FDroid,
+            "amazon" => Self::Amazon,
+            "chrome_web_store" => Self::ChromeWebStore,
+            "chromeos_play" => Self::ChromeOsPlay,
+            "webapp" => Self::Webapp,
+            other => Self::Other(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Play => "play",
+            Self::Itunes => "itunes",
+            Self::Windows => "windows",
+            Self::FDroid => "f-droid",
+            Self::Amazon => "amazon",
+            Self::ChromeWebStore => "chrome_web_store",
+            Self::ChromeOsPlay => "chromeos_play",
+            Self::Webapp => "webapp",
+            Self::Other(other) => other,
+        })
+    }
+}
+
+/// The category of the web application.
+///
+/// Known values are taken from the [list of known categories](https://github.com/w3c/manifest/wiki/Categories)
+/// maintained by the W3C and are matched case-insensitively, while unknown values are
+/// preserved as [`Other`][Category::Other].
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Eq, PartialEq, Clone, Hash)]
+pub enum Category {
+    /// Books and reading.
+    Books,
+
+    /// Business and professional tools.
+    Business,
+
+    /// Education and learning.
+    Education,
+
+    /// Entertainment.
+    Entertainment,
+
+    /// Finance and banking.
+    Finance,
+
+    /// Fitness and exercise.
+    Fitness,
+
+    /// Food and drink.
+    Food,
+
+    /// Games.
+    Games,
+
+    /// Government services.
+    Government,
+
+    /// Health.
+    Health,
+
+    /// Content and applications for kids.
+    Kids,
+
+    /// Lifestyle.
+    Lifestyle,
+
+    /// Magazines.
+    Magazines,
+
+    /// Medical.
+    Medical,
+
+    /// Music and audio.
+    Music,
+
+    /// Navigation and maps.
+    Navigation,
+
+    /// News.
+    News,
+
+    /// Personalization.
+    Personalization,
+
+    /// Photo and video.
+    Photo,
+
+    /// Politics.
+    Politics,
+
+    /// Productivity.
+    Productivity,
+
+    /// Security.
+    Security,
+
+    /// Shopping.
+    Shopping,
+
+    /// Social networking.
+    Social,
+
+    /// Sports.
+    Sports,
+
+    /// Travel.
+    Travel,
+
+    /// Utilities.
+    Utilities,
+
+    /// Weather.
+    Weather,
+
+    /// Category that is not known to this crate.
+    Other(String),
+}
+
+impl FromStr for Category {
+    type Err = Infallible;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(match string.to_ascii_lowercase().as_str() {
+            "books" => Self::Books,
+            "business" => Self::Business,
+            "education" => Self::Education,
+            "entertainment" => Self::Entertainment,
+            "finance" => Self::Finance,
+            "fitness" => Self::Fitness,
+            "food" => Self::Food,
+            "games" => Self::Games,
+            "government" => Self::Government,
+            "health" => Self::Health,
+            "kids" => Self::Kids,
+            "lifestyle" => Self::Lifestyle,
+            "magazines" => Self::Magazines,
+            "medical" => Self::Medical,
+            "music" => Self::Music,
+            "navigation" => Self::Navigation,
+            "news" => Self::News,
+            "personalization" => Self::Personalization,
+            "photo" => Self::Photo,
+            "politics" => Self::Politics,
+            "productivity" => Self::Productivity,
+            "security" => Self::Security,
+            "shopping" => Self::Shopping,
+            "social" => Self::Social,
+            "sports" => Self::Sports,
+            "travel" => Self::Travel,
+            "utilities" => Self::Utilities,
+            "weather" => Self::Weather,
+            _ => Self::Other(string.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Books => "books",
+            Self::Business => "business",
+            Self::Education => "education",
+            Self::Entertainment => "entertainment",
+            Self::Finance => "finance",
+            Self::Fitness => "fitness",
+            Self::Food => "food",
+            Self::Games => "games",
+            Self::Government => "government",
+            Self::Health => "health",
+            Self::Kids => "kids",
+            Self::Lifestyle => "lifestyle",
+            Self::Magazines => "magazines",
+            Self::Medical => "medical",
+            Self::Music => "music",
+            Self::Navigation => "navigation",
+            Self::News => "news",
+            Self::Personalization => "personalization",
+            Self::Photo => "photo",
+            Self::Politics => "politics",
+            Self::Productivity => "productivity",
+            Self::Security => "security",
+            Self::Shopping => "shopping",
+            Self::Social => "social",
+            Self::Sports => "sports",
+            Self::Travel => "travel",
+            Self::Utilities => "utilities",
+            Self::Weather => "weather",
+            Self::Other(other) => other,
+        })
+    }
+}
+
+/// The location of a human-readable text in the manifest.
+///
+/// It is passed to the callback of [`visit_texts`][crate::WebAppManifest::visit_texts].
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum TextContext {
+    /// The `name` member.
+    Name,
+
+    /// The `short_name` member.
+    ShortName,
+
+    /// The `description` member.
+    Description,
+
+    /// The `label` of the icon at the index.
+    IconLabel(usize),
+
+    /// The `label` of the screenshot at the index.
+    ScreenshotLabel(usize),
+
+    /// The `name` of the shortcut at the index.
+    ShortcutName(usize),
+
+    /// The `short_name` of the shortcut at the index.
+    ShortcutShortName(usize),
+
+    /// The `description` of the shortcut at the index.
+    ShortcutDescription(usize),
+
+    /// The `label` of the icon of the shortcut at the indices.
+    ShortcutIconLabel(usize, usize),
+
+    /// The `name` of the file handler at the index.
+    FileHandlerName(usize),
+
+    /// The `label` of the icon of the file handler at the indices.
+    FileHandlerIconLabel(usize, usize),
+
+    /// The `name` of the widget at the index.
+    WidgetName(usize),
+
+    /// The `short_name` of the widget at the index.
+    WidgetShortName(usize),
+
+    /// The `description` of the widget at the index.
+    WidgetDescription(usize),
+
+    /// The `label` of the icon of the widget at the indices.
+    WidgetIconLabel(usize, usize),
+
+    /// The `label` of the screenshot of the widget at the indices.
+    WidgetScreenshotLabel(usize, usize),
+
+    /// The `name` of the translation at the index.
+    ///
+    /// Translations are indexed in the order of their language tags.
+    TranslationName(usize),
+
+    /// The `short_name` of the translation at the index.
+    TranslationShortName(usize),
+
+    /// The `description` of the translation at the index.
+    TranslationDescription(usize),
+
+    /// The `name` of the shortcut of the translation at the indices.
+    TranslationShortcutName(usize, usize),
+
+    /// The `short_name` of the shortcut of the translation at the indices.
+    TranslationShortcutShortName(usize, usize),
+
+    /// The `description` of the shortcut of the translation at the indices.
+    TranslationShortcutDescription(usize, usize),
+
+    /// The `label` of the icon of the translation at the indices.
+    TranslationIconLabel(usize, usize),
+}
+
+/// The location of a URL in the manifest.
+///
+/// It is passed to the callback of [`visit_urls`][crate::WebAppManifest::visit_urls].
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum UrlContext {
+    /// The `start_url` member.
+    StartUrl,
+
+    /// The `scope` member.
+    Scope,
+
+    /// The `id` member.
+    Id,
+
+    /// The `src` of the icon at the index.
+    IconSrc(usize),
+
+    /// The `src` of the screenshot at the index.
+    ScreenshotSrc(usize),
+
+    /// The `url` of the shortcut at the index.
+    ShortcutUrl(usize),
+
+    /// The `src` of the icon of the shortcut at the indices.
+    ShortcutIconSrc(usize, usize),
+
+    /// The `url` of the protocol handler at the index.
+    ProtocolHandlerUrl(usize),
+
+    /// The `action` of the file handler at the index.
+    FileHandlerAction(usize),
+
+    /// The `src` of the icon of the file handler at the indices.
+    FileHandlerIconSrc(usize, usize),
+
+    /// The `action` of the share target.
+    ShareTargetAction,
+
+    /// The `new_note_url` of the note-taking member.
+    NewNoteUrl,
+
+    /// The `url` of the related application at the index.
+    RelatedApplicationUrl(usize),
+
+    /// The `src` of the icon of the home tab at the index.
+    HomeTabIconSrc(usize),
 
-    /// Right-to-left text.
-    ///
-    /// The implementation should override the [Rule P3](https://www.unicode.org/reports/tr9/tr9-42.html#P3)
-    /// of [Unicode Bidirectional Algorithm](https://www.unicode.org/reports/tr9/tr9-42.html),
-    /// setting the paragraph embedding level to `1`.
-    Rtl,
-}
+    /// The `url` of the new tab button.
+    NewTabButtonUrl,
 
-impl Default for Direction {
-    #[inline]
-    fn default() -> Self {
-        Self::Auto
-    }
-}
+    /// The `src` of the service worker.
+    ServiceWorkerSrc,
 
-/// The preferred display mode of the web application.
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
-#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
-#[serde(rename_all = "kebab-case")]
-pub enum Display {
-    /// Opens the web application in a conventional browser tab or new window,
-    /// depending on the browser and platform.
-    ///
-    /// This is the default variant and must be supported by the user agent.
-    Browser,
+    /// The `scope` of the service worker.
+    ServiceWorkerScope,
 
-    /// Opens the web application with browser UI elements hidden and takes
-    /// up the entirety of the available display area.
-    ///
-    /// If not supported, the user agent must fall back to the `standalone` mode.
-    Fullscreen,
+    /// The `ms_ac_template` of the widget at the index.
+    WidgetTemplate(usize),
 
-    /// Opens the web application to look and feel like a standalone native
-    /// application. This can include the application having a different window,
-    /// its own icon in the application launcher, etc. In this mode, the user agent
-    /// will exclude standard browser UI elements such as an URL bar, but can
-    /// include other system UI elements such as a status bar and/or system
-    /// back button.
-    ///
-    /// If not supported, the user agent must fall back to the `minimal-ui` mode.
-    Standalone,
+    /// The `data` of the widget at the index.
+    WidgetData(usize),
 
-    /// Opens the web application to look and feel like a standalone native
-    /// application, but provides the end-user with some means to access a minimal
-    /// set of UI elements for controlling navigation. A user agent can include
-    /// other platform specific UI elements, such as "share" and "print" buttons or
-    /// whatever is customary on the platform and user agent.
-    ///
-    /// If not supported, the user agent must fall back to the `browser` mode.
-    MinimalUi,
-}
+    /// The `src` of the icon of the widget at the indices.
+    WidgetIconSrc(usize, usize),
 
-impl Default for Display {
-    #[inline]
-    fn default() -> Self {
-        Self::Browser
-    }
+    /// The `src` of the screenshot of the widget at the indices.
+    WidgetScreenshotSrc(usize, usize),
+
+    /// The `src` of the icon of the translation at the indices.
+    ///
+    /// Translations are indexed in the order of their language tags.
+    TranslationIconSrc(usize, usize),
 }
 
-/// The preferred orientation of the web application.
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+/// The form factor of the device for which a screenshot is intended.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Hash)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
-#[serde(rename_all = "kebab-case")]
-pub enum Orientation {
-    /// Any is an orientation that means the screen can be locked to any one
-    /// of the `portrait-primary`, `portrait-secondary`, `landscape-primary`
-    /// and `landscape-secondary`.
+#[serde(rename_all = "lowercase")]
+pub enum FormFactor {
+    /// The screenshot is intended for narrow screens, such as mobile devices.
+    Narrow,
+
+    /// The screenshot is intended for wide screens, such as desktop computers.
     ///
     /// This is the default variant.
-    Any,
-
-    /// Natural is an orientation that refers to either `portrait-primary` or
-    /// `landscape-primary` depending on the device's usual orientation. This
-    /// orientation is usually provided by the underlying operating system.
-    Natural,
-
-    /// Landscape is an orientation where the screen width is greater than the
-    /// screen height.
-    Landscape,
-
-    /// Portrait is an orientation where the screen width is less than or equal
-    /// to the screen height.
-    Portrait,
-
-    /// Landscape-primary is an orientation where the screen width is greater than
-    /// the screen height. If the device's natural orientation is landscape, then it
-    /// is in landscape-primary when held in that position.
-    LandscapePrimary,
+    Wide,
 
-    /// Landscape-secondary is an orientation where the screen width is greater than
-    /// the screen height. If the device's natural orientation is landscape, then it
-    /// is in landscape-secondary when rotated 180° from its natural orientation.
-    LandscapeSecondary,
+    /// Form factor that is not known to this crate.
+    ///
+    /// User agents ignore unknown values and treat the screenshot as suitable for
+    /// all form factors.
+    #[serde(untagged)]
+    Unknown(String),
+}
 
-    /// Portrait-primary is an orientation where the screen width is less than or equal
-    /// to the screen height. If the device's natural orientation is portrait, then it
-    /// is in portrait-primary when held in that position.
-    PortraitPrimary,
+impl FormFactor {
+    /// Returns the keyword of the form factor, as it is used in the manifest.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Narrow => "narrow",
+            Self::Wide => "wide",
+            Self::Unknown(other) => other,
+        }
+    }
 
-    /// Portrait-secondary is an orientation where the screen width is less than or equal
-    /// to the screen height. If the device's natural orientation is portrait, then it
-    /// is in portrait-secondary when rotated 180° from its natural orientation.
-    PortraitSecondary,
+    /// Converts the keyword to the form factor, ignoring the ASCII case.
+    fn from_keyword(keyword: &str) -> Self {
+        match keyword.to_ascii_lowercase().as_str() {
+            "narrow" => Self::Narrow,
+            "wide" => Self::Wide,
+            _ => Self::Unknown(keyword.to_string()),
+        }
+    }
 }
 
-impl Default for Orientation {
+impl Default for FormFactor {
     #[inline]
     fn default() -> Self {
-        Self::Any
+        Self::Wide
     }
 }
 
-/// The HTTP request method for the web share target.
-#[derive(Display, FromStr, Debug, Eq, PartialEq, Clone, Copy, Hash)]
-#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema), schemars(rename_all = "UPPERCASE"))]
-#[display(style = "UPPERCASE")]
-pub enum ShareTargetMethod {
-    /// The web share target uses the GET method.
-    ///
-    /// This is the default variant.
-    #[from_str(regex = "(?i)GET")]
-    Get,
+impl<'de> Deserialize<'de> for FormFactor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_keyword(&String::deserialize(deserializer)?))
+    }
+}
 
-    /// The web share target uses the POST method.
-    #[from_str(regex = "(?i)POST")]
-    Post,
+impl FromStr for FormFactor {
+    type Err = Infallible;
+
+    /// Parses the form factor from the keyword, ignoring the ASCII case. Keywords that
+    /// are not known are preserved as the `Unknown` variant.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_keyword(string))
+    }
 }
 
-impl Default for ShareTargetMethod {
-    #[inline]
-    fn default() -> Self {
-        Self::Get
+impl fmt::Display for FormFactor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
     }
 }
 
-/// The encoding in the body of a POST request for the web share target.
-/// It is ignored when the method is GET.
-#[derive(Display, FromStr, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+/// The color scheme preferred by the user, as reported by the `prefers-color-scheme` media feature.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
-pub enum ShareTargetEnctype {
-    /// The web share target uses `application/x-www-form-urlencoded` encoding.
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    /// The user prefers a light color scheme.
     ///
     /// This is the default variant.
-    #[display("application/x-www-form-urlencoded")]
-    #[from_str(regex = "(?i)application/x-www-form-urlencoded")]
-    #[cfg_attr(feature = "schemars", schemars(rename = "application/x-www-form-urlencoded"))]
-    UrlEncoded,
+    Light,
 
-    /// The web share target uses `multipart/form-data` encoding.
-    #[display("multipart/form-data")]
-    #[from_str(regex = "(?i)multipart/form-data")]
-    #[cfg_attr(feature = "schemars", schemars(rename = "multipart/form-data"))]
-    FormData,
+    /// The user prefers a dark color scheme.
+    Dark,
 }
 
-impl Default for ShareTargetEnctype {
+impl Default for ColorScheme {
     #[inline]
     fn default() -> Self {
-        Self::UrlEncoded
+        Self::Light
     }
 }
 
 /// The size of the image.
-#[derive(Display, FromStr, Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+///
+/// Sizes are ordered by their area, with [`Any`][ImageSize::Any] being larger than all fixed
+/// sizes. Fixed sizes with the same area are ordered by their width and then by their height,
+/// so the order is consistent with equality.
+#[derive(Display, FromStr, Debug, Eq, PartialEq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema), schemars(untagged))]
 pub enum ImageSize {
     /// Image is `{0}` by `{1}` pixels big.
@@ -286,6 +1734,32 @@ pub enum ImageSize {
     Any,
 }
 
+impl ImageSize {
+    /// Returns the width of the image in pixels, or `None` if the image can support any size.
+    pub fn width(&self) -> Option<u32> {
+        match self {
+            Self::Fixed(width, _) => Some(*width),
+            Self::Any => None,
+        }
+    }
+
+    /// Returns the height of the image in pixels, or `None` if the image can support any size.
+    pub fn height(&self) -> Option<u32> {
+        match self {
+            Self::Fixed(_, height) => Some(*height),
+            Self::Any => None,
+        }
+    }
+
+    /// Returns the area of the image in pixels, or `None` if the image can support any size.
+    pub fn area(&self) -> Option<u64> {
+        match self {
+            Self::Fixed(width, height) => Some(u64::from(*width) * u64::from(*height)),
+            Self::Any => None,
+        }
+    }
+}
+
 impl Default for ImageSize {
     #[inline]
     fn default() -> Self {
@@ -293,6 +1767,25 @@ impl Default for ImageSize {
     }
 }
 
+impl Ord for ImageSize {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Any, Self::Any) => Ordering::Equal,
+            (Self::Any, Self::Fixed(..)) => Ordering::Greater,
+            (Self::Fixed(..), Self::Any) => Ordering::Less,
+            (Self::Fixed(width1, height1), Self::Fixed(width2, height2)) => {
+                self.area().cmp(&other.area()).then(width1.cmp(width2)).then(height1.cmp(height2))
+            }
+        }
+    }
+}
+
+impl PartialOrd for ImageSize {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(feature = "schemars")]
 fn image_size_fixed(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
     schemars::schema::SchemaObject {
@@ -316,6 +1809,47 @@ fn image_size_any(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::S
     .into()
 }
 
+/// The pixel density of the image.
+///
+/// Densities are compared with [`f64::total_cmp`], so they are totally ordered and can be
+/// used in resources that implement [`Eq`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Density(pub f64);
+
+impl PartialEq for Density {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Density {}
+
+impl Ord for Density {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Density {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<f64> for Density {
+    fn from(density: f64) -> Self {
+        Self(density)
+    }
+}
+
+impl From<Density> for f64 {
+    fn from(density: Density) -> Self {
+        density.0
+    }
+}
+
 /// The purpose of the image.
 #[derive(Display, FromStr, Debug, Eq, PartialEq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema), schemars(rename_all = "kebab-case"))]
@@ -324,15 +1858,18 @@ pub enum ImagePurpose {
     /// The user agent is free to display the icon in any context.
     ///
     /// This is the default variant.
+    #[from_str(regex = "(?i)any")]
     Any,
 
     /// A user agent can present this icon where a monochrome icon with a solid fill is needed.
     /// The color information in the icon is discarded and only the alpha data is used.
+    #[from_str(regex = "(?i)monochrome")]
     Monochrome,
 
     /// The image is designed with icon masks and safe zone in mind, such that any part of
     /// the image that is outside the safe zone can safely be ignored and masked away by
     /// the user agent.
+    #[from_str(regex = "(?i)maskable")]
     Maskable,
 }
 
@@ -419,6 +1956,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_display_override_modes() {
+        let modes = [
+            "browser",
+            "fullscreen",
+            "standalone",
+            "minimal-ui",
+            "window-controls-overlay",
+            "tabbed",
+            "borderless",
+            "picture-in-picture",
+        ];
+
+        for mode in modes {
+            let parsed = DisplayOverride::from_str(mode).unwrap();
+            assert_eq!(parsed.to_string(), mode);
+        }
+
+        assert_eq!(DisplayOverride::from_str("picture-in-picture").unwrap(), DisplayOverride::Unknown("picture-in-picture".to_string()));
+    }
+
+    #[test]
+    fn test_display_override_into_display() {
+        assert_eq!(Display::try_from(DisplayOverride::Standalone), Ok(Display::Standalone));
+        assert_eq!(Display::try_from(DisplayOverride::from(Display::MinimalUi)), Ok(Display::MinimalUi));
+        assert_eq!(Display::try_from(DisplayOverride::Tabbed), Err(DisplayOverride::Tabbed));
+    }
+
+    #[test]
+    fn test_allowlist_entries() {
+        assert_eq!(AllowlistEntry::from_str("*").unwrap(), AllowlistEntry::Any);
+        assert_eq!(AllowlistEntry::from_str("self").unwrap(), AllowlistEntry::SelfOrigin);
+        assert_eq!(AllowlistEntry::from_str("'self'").unwrap(), AllowlistEntry::SelfOrigin);
+        assert_eq!(AllowlistEntry::from_str("'none'").unwrap(), AllowlistEntry::None);
+        assert_eq!(AllowlistEntry::from_str("https://example.com").unwrap().to_string(), "https://example.com");
+        assert_eq!(AllowlistEntry::from_str("example").unwrap(), AllowlistEntry::Unknown("example".to_string()));
+
+        let app = AbsoluteUrl::parse("https://app.example").unwrap();
+        let other = AbsoluteUrl::parse("https://other.example/page").unwrap();
+
+        assert!(AllowlistEntry::SelfOrigin.matches(&app, &app));
+        assert!(!AllowlistEntry::SelfOrigin.matches(&other, &app));
+        assert!(AllowlistEntry::from_str("https://other.example").unwrap().matches(&other, &app));
+        assert!(AllowlistEntry::Any.matches(&other, &app));
+        assert!(!AllowlistEntry::None.matches(&app, &app));
+    }
+
+    #[test]
+    fn test_unknown_keywords() {
+        let display: Display = serde_json::from_str(r#""picture-in-picture""#).unwrap();
+        assert_eq!(display, Display::Unknown("picture-in-picture".to_string()));
+        assert_eq!(serde_json::to_string(&display).unwrap(), r#""picture-in-picture""#);
+
+        let orientation: Orientation = serde_json::from_str(r#""sideways""#).unwrap();
+        assert_eq!(orientation, Orientation::Unknown("sideways".to_string()));
+
+        let direction: Direction = serde_json::from_str(r#""rtl""#).unwrap();
+        assert_eq!(direction, Direction::Rtl);
+
+        let direction: Direction = serde_json::from_str(r#""ttb""#).unwrap();
+        assert_eq!(direction, Direction::Unknown("ttb".to_string()));
+        assert_eq!(serde_json::to_string(&direction).unwrap(), r#""ttb""#);
+
+        let form_factor: FormFactor = serde_json::from_str(r#""foldable""#).unwrap();
+        assert_eq!(form_factor, FormFactor::Unknown("foldable".to_string()));
+        assert_eq!(serde_json::to_string(&form_factor).unwrap(), r#""foldable""#);
+    }
+
+    #[test]
+    fn test_case_insensitive_keywords() {
+        let display: Display = serde_json::from_str(r#""Standalone""#).unwrap();
+        assert_eq!(display, Display::Standalone);
+        assert_eq!(serde_json::to_string(&display).unwrap(), r#""standalone""#);
+
+        let orientation: Orientation = serde_json::from_str(r#""PORTRAIT-Primary""#).unwrap();
+        assert_eq!(orientation, Orientation::PortraitPrimary);
+        assert_eq!(serde_json::to_string(&orientation).unwrap(), r#""portrait-primary""#);
+
+        let direction: Direction = serde_json::from_str(r#""RTL""#).unwrap();
+        assert_eq!(direction, Direction::Rtl);
+
+        let purpose = ImagePurpose::from_str("Maskable").unwrap();
+        assert_eq!(purpose, ImagePurpose::Maskable);
+        assert_eq!(purpose.to_string(), "maskable");
+    }
+
+    #[test]
+    fn test_keyword_string_conversion() {
+        assert_eq!(Display::from_str("minimal-ui").unwrap(), Display::MinimalUi);
+        assert_eq!(Display::MinimalUi.to_string(), "minimal-ui");
+        assert_eq!(Display::from_str("Kiosk").unwrap().to_string(), "Kiosk");
+
+        assert_eq!(Orientation::from_str("landscape-secondary").unwrap(), Orientation::LandscapeSecondary);
+        assert_eq!(Orientation::LandscapeSecondary.to_string(), "landscape-secondary");
+
+        assert_eq!("ltr".parse::<Direction>().unwrap(), Direction::Ltr);
+        assert_eq!(Direction::Auto.as_str(), "auto");
+    }
+
     #[test]
     fn test_any_image_size() {
         let deserialized = ImageSize::from_str("aNy").unwrap();
@@ -436,4 +2072,173 @@ mod tests {
         let serialized = deserialized.to_string();
         assert_eq!(serialized, "64x128");
     }
+
+    #[test]
+    fn test_image_size_helpers() {
+        let size = ImageSize::Fixed(64, 32);
+        assert_eq!(size.width(), Some(64));
+        assert_eq!(size.height(), Some(32));
+        assert_eq!(size.area(), Some(2048));
+        assert_eq!(ImageSize::Any.area(), None);
+    }
+
+    #[test]
+    fn test_image_size_ordering() {
+        let mut sizes = vec![
+            ImageSize::Any,
+            ImageSize::Fixed(512, 512),
+            ImageSize::Fixed(32, 128),
+            ImageSize::Fixed(64, 64),
+            ImageSize::Fixed(16, 16),
+        ];
+        sizes.sort();
+
+        assert_eq!(sizes, vec![
+            ImageSize::Fixed(16, 16),
+            ImageSize::Fixed(32, 128),
+            ImageSize::Fixed(64, 64),
+            ImageSize::Fixed(512, 512),
+            ImageSize::Any,
+        ]);
+
+        assert_eq!(sizes.iter().max(), Some(&ImageSize::Any));
+        assert!(ImageSize::Fixed(1, 100) < ImageSize::Fixed(100, 1));
+    }
+
+    #[test]
+    fn test_url_helpers() {
+        let base = AbsoluteUrl::parse("https://example.com/app/manifest.json").unwrap();
+        let absolute = Url::Absolute(AbsoluteUrl::parse("https://example.org/").unwrap());
+        let relative = Url::Relative("icons/icon.png".to_string());
+
+        assert_eq!(absolute.as_str(), Some("https://example.org/"));
+        assert_eq!(relative.as_str(), Some("icons/icon.png"));
+        assert_eq!(Url::Unknown.as_str(), None);
+
+        assert!(absolute.is_absolute() && !absolute.is_relative());
+        assert!(relative.is_relative() && !relative.is_absolute());
+        assert!(!Url::Unknown.is_absolute() && !Url::Unknown.is_relative());
+        assert!(Url::Unknown.is_unknown() && !relative.is_unknown());
+
+        assert_eq!(absolute.join(&base).unwrap().as_str(), "https://example.org/");
+        assert_eq!(relative.join(&base).unwrap().as_str(), "https://example.com/app/icons/icon.png");
+        assert_eq!(Url::Unknown.join(&base).unwrap_err(), ManifestError::InvalidUnknownUrl);
+    }
+
+    #[test]
+    fn test_url_deserialize() {
+        let urls: Vec<Url> =
+            serde_json::from_str(r#"["https://example.com/", "/app/", "", null]"#).unwrap();
+        assert_eq!(urls, vec![
+            Url::Absolute(AbsoluteUrl::parse("https://example.com/").unwrap()),
+            Url::Relative("/app/".to_string()),
+            Url::Relative("".to_string()),
+            Url::Unknown,
+        ]);
+
+        let error = serde_json::from_str::<Url>("42").unwrap_err();
+        assert_eq!(error.to_string(), "invalid type: integer `42`, expected a URL string or null at line 1 column 2");
+    }
+
+    #[test]
+    fn test_url_display() {
+        let absolute = Url::Absolute(AbsoluteUrl::parse("https://example.com/app").unwrap());
+        assert_eq!(absolute.to_string(), "https://example.com/app");
+        assert_eq!(Url::Relative("../icon.png".to_string()).to_string(), "../icon.png");
+        assert_eq!(Url::Unknown.to_string(), "<unknown>");
+
+        let error = ManifestError::NotAbsolute { url: Url::Relative("/index.html".to_string()) };
+        assert_eq!(error.to_string(), "Provided URL (/index.html) cannot be converted to an absolute URL");
+    }
+
+    #[test]
+    fn test_url_hosts() {
+        let url = Url::from_str("https://xn--bcher-kva.example/index.html").unwrap();
+
+        assert_eq!(url.ascii_host(), Some("xn--bcher-kva.example"));
+        assert_eq!(url.unicode_host(), Some("bücher.example".to_string()));
+        assert_eq!(url.display_host(), Some("bücher.example".to_string()));
+
+        let url = Url::from_str("https://bücher.example/index.html").unwrap();
+        assert_eq!(url.ascii_host(), Some("xn--bcher-kva.example"));
+
+        assert_eq!(Url::Relative("/index.html".to_string()).ascii_host(), None);
+        assert_eq!(Url::Unknown.unicode_host(), None);
+    }
+
+    #[test]
+    fn test_confusable_hosts() {
+        // Cyrillic characters that look like "apple"
+        let url = Url::from_str("https://xn--80ak6aa92e.com").unwrap();
+        assert_eq!(url.unicode_host(), Some("аррӏе.com".to_string()));
+        assert_eq!(url.display_host(), Some("xn--80ak6aa92e.com".to_string()));
+
+        // Latin "paypal" with Cyrillic "а"
+        let url = Url::from_str("https://xn--pypal-4ve.com").unwrap();
+        assert_eq!(url.display_host(), Some("xn--pypal-4ve.com".to_string()));
+
+        // Legitimate Cyrillic domain
+        let url = Url::from_str("https://xn--e1afmkfd.xn--p1ai").unwrap();
+        assert_eq!(url.display_host(), Some("пример.рф".to_string()));
+    }
+
+    #[test]
+    fn test_scope_matching_segments() {
+        let matching = ScopeMatching::Segments;
+
+        assert!(matching.matches_path("/app", "/app"));
+        assert!(matching.matches_path("/app/", "/app"));
+        assert!(matching.matches_path("/app/page", "/app"));
+        assert!(matching.matches_path("/app/page", "/app/"));
+        assert!(matching.matches_path("/application", "/"));
+        assert!(!matching.matches_path("/application", "/app"));
+        assert!(!matching.matches_path("/app", "/app/"));
+    }
+
+    #[test]
+    fn test_scope_matching_prefix() {
+        let matching = ScopeMatching::Prefix;
+
+        assert!(matching.matches_path("/app/page", "/app"));
+        assert!(matching.matches_path("/application", "/app"));
+        assert!(!matching.matches_path("/app", "/app/"));
+    }
+
+    #[test]
+    fn test_platform() {
+        for value in &[
+            "play",
+            "itunes",
+            "windows",
+            "f-droid",
+            "amazon",
+            "chrome_web_store",
+            "chromeos_play",
+            "webapp",
+        ] {
+            let platform = Platform::from_str(value).unwrap();
+            assert!(!matches!(platform, Platform::Other(_)));
+            assert_eq!(platform.to_string(), *value);
+        }
+
+        let platform = Platform::from_str("custom-store").unwrap();
+        assert_eq!(platform, Platform::Other("custom-store".to_string()));
+        assert_eq!(platform.to_string(), "custom-store");
+    }
+
+    #[test]
+    fn test_category() {
+        let categories: Vec<Category> =
+            serde_json::from_str(r#"["games", "Travel", "UTILITIES", "Recipes"]"#).unwrap();
+
+        assert_eq!(categories, vec![
+            Category::Games,
+            Category::Travel,
+            Category::Utilities,
+            Category::Other("Recipes".to_string()),
+        ]);
+
+        let serialized = serde_json::to_string(&categories).unwrap();
+        assert_eq!(serialized, r#"["games","travel","utilities","Recipes"]"#);
+    }
 }
@@ -0,0 +1,821 @@
+//! Contains manifest validation.
+//!
+//! Unlike [processing][crate::WebAppManifest::process], which only fails on problems
+//! that prevent the manifest from being used, validation reports all problems that
+//! could cause parts of the manifest to be ignored or misbehave in user agents.
+//!
+//! Validation of URL-related rules requires the manifest to be processed first.
+//! Rules that require absolute URLs are skipped for unprocessed manifests.
+
+#![allow(missing_docs)]
+
+use std::str::FromStr;
+
+use thiserror::Error;
+use url::Host;
+
+use crate::types::*;
+use crate::{fold_search_text, is_within_scope, WebAppManifest};
+
+/// The severity of the validation issue.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum Severity {
+    /// The issue does not prevent the member from being used, but it is likely a mistake.
+    Warning,
+
+    /// The issue causes the member to be ignored or misbehave in user agents.
+    Error,
+}
+
+/// A validation issue kind represents all problems that can be found during validation.
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+pub enum ValidationIssueKind {
+    /// When the media type cannot be parsed.
+    #[error("Provided media type ({media_type}) is not valid")]
+    InvalidMediaType { media_type: String },
+
+    /// When the file extension does not start with a dot.
+    #[error("Provided file extension ({extension}) does not start with a dot")]
+    InvalidFileExtension { extension: String },
+
+    /// When the file handler does not accept any files.
+    #[error("File handler does not accept any files")]
+    EmptyAccept,
+
+    /// When the URL is not within the scope.
+    #[error("Provided URL ({url}) is not within the scope ({scope})")]
+    NotWithinScope { url: AbsoluteUrl, scope: AbsoluteUrl },
+
+    /// When the URL is not served over a secure connection.
+    #[error("Provided URL ({url}) is not secure")]
+    InsecureUrl { url: AbsoluteUrl },
+
+    /// When the host is potentially visually confusable with another host.
+    #[error("Provided host ({host}) may be confused with another host")]
+    ConfusableHost { host: String },
+
+    /// When the name mixes scripts in a way that may imitate another name.
+    #[error("Provided name ({name}) may be confused with another name")]
+    ConfusableName { name: String },
+
+    /// When the name is padded with whitespace or invisible characters.
+    #[error("Provided name ({name:?}) contains padding or invisible characters")]
+    PaddedName { name: String },
+
+    /// When the name imitates a protected name, such as a known brand.
+    #[error("Provided name ({name}) imitates the protected name ({protected})")]
+    ImitatedName { name: String, protected: String },
+
+    /// When the member is deprecated or superseded by another member.
+    #[error("Provided member ({member}) is deprecated{}", replacement.map(|replacement| format!(", use `{}` instead", replacement)).unwrap_or_default())]
+    DeprecatedMember { member: String, replacement: Option<&'static str> },
+
+    /// When the image size cannot be parsed.
+    #[error("Provided image size ({size}) is not valid and will be ignored")]
+    InvalidImageSize { size: String },
+
+    /// When the image purpose is not known.
+    #[error("Provided image purpose ({purpose}) is not known and will be ignored")]
+    UnknownImagePurpose { purpose: String },
+
+    /// When the display mode is not known.
+    #[error("Provided display mode ({mode}) is not known")]
+    UnknownDisplayMode { mode: String },
+
+    /// When the value of a keyword member (such as `dir` or `orientation`) is not known.
+    #[error("Provided value ({value}) is not known and will be ignored")]
+    UnknownKeyword { value: String },
+
+    /// When the display mode is specified multiple times.
+    #[error("Provided display mode ({mode}) is specified multiple times")]
+    DuplicateDisplayMode { mode: String },
+
+    /// When the display mode can never be used because a preceding display mode is always supported.
+    #[error("Provided display mode ({mode}) is unreachable because it follows the browser mode")]
+    UnreachableDisplayMode { mode: String },
+}
+
+/// A validation issue represents a single problem found in the manifest.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ValidationIssue {
+    /// The severity of the issue.
+    pub severity: Severity,
+
+    /// The location of the affected member, such as `file_handlers[0].accept`.
+    pub path: String,
+
+    /// The kind of the issue.
+    pub kind: ValidationIssueKind,
+}
+
+/// A validation report contains all issues found in the manifest.
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+pub struct ValidationReport {
+    /// The issues found in the manifest, in the order of the manifest members.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if there are no issues with the error severity.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns all issues with the error severity.
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Returns all issues with the warning severity.
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn push(&mut self, severity: Severity, path: String, kind: ValidationIssueKind) {
+        self.issues.push(ValidationIssue { severity, path, kind });
+    }
+}
+
+/// The validation profile determines which rules apply and how strict they are.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum ValidationProfile {
+    /// Validates the manifest as it would be served to users.
+    ///
+    /// This is the default variant.
+    Production,
+
+    /// Validates the manifest during local development.
+    ///
+    /// URLs on `localhost` and loopback addresses are treated as secure even when
+    /// they are not served over HTTPS, and other insecure URLs are only reported
+    /// as warnings.
+    Development,
+}
+
+impl Default for ValidationProfile {
+    #[inline]
+    fn default() -> Self {
+        Self::Production
+    }
+}
+
+/// Options that control how the manifest is validated.
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+pub struct ValidationOptions {
+    /// The validation profile.
+    pub profile: ValidationProfile,
+
+    /// Names that should not be imitated by the application name, such as names of
+    /// known brands. Stores can use it to detect applications that impersonate others.
+    ///
+    /// Names are compared case-insensitively, ignoring accents, punctuation and
+    /// characters that look like Latin characters.
+    pub protected_names: Vec<String>,
+}
+
+/// A deprecated manifest member with its modern replacement.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct DeprecatedMember {
+    /// The name of the deprecated member.
+    pub member: &'static str,
+
+    /// The name of the member that supersedes it, if any.
+    pub replacement: Option<&'static str>,
+}
+
+/// The table of deprecated or superseded manifest members.
+pub const DEPRECATED_MEMBERS: &[DeprecatedMember] = &[
+    // Service workers should be registered by the document instead
+    DeprecatedMember { member: "serviceworker", replacement: None },
+    DeprecatedMember { member: "capture_links", replacement: Some("launch_handler") },
+    DeprecatedMember { member: "url_handlers", replacement: Some("scope_extensions") },
+];
+
+/// Validates the members of the unparsed manifest.
+///
+/// Unlike [`validate`][WebAppManifest::validate], this works on the raw JSON document,
+/// so it can detect members that are not (or no longer) represented by the manifest type,
+/// such as the deprecated members from [`DEPRECATED_MEMBERS`], and image tokens that are
+/// ignored when the manifest is parsed.
+///
+/// # Returns
+///
+/// A report containing all found issues.
+///
+pub fn validate_members(document: &serde_json::Value) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let Some(members) = document.as_object() else {
+        return report;
+    };
+
+    for deprecated in DEPRECATED_MEMBERS {
+        if members.contains_key(deprecated.member) {
+            let kind = ValidationIssueKind::DeprecatedMember {
+                member: deprecated.member.to_string(),
+                replacement: deprecated.replacement,
+            };
+
+            report.push(Severity::Warning, deprecated.member.to_string(), kind);
+        }
+    }
+
+    validate_image_tokens(members, &mut report);
+
+    report
+}
+
+/// Returns the elements of the JSON array, or an empty slice if the value is not an array.
+fn array(value: Option<&serde_json::Value>) -> &[serde_json::Value] {
+    value.and_then(serde_json::Value::as_array).map(Vec::as_slice).unwrap_or_default()
+}
+
+/// Returns the tokens of the space-separated string or the array of space-separated strings.
+fn tokens(value: Option<&serde_json::Value>) -> Vec<&str> {
+    match value {
+        Some(serde_json::Value::String(string)) => string.split_ascii_whitespace().collect(),
+        Some(serde_json::Value::Array(array)) => array
+            .iter()
+            .filter_map(serde_json::Value::as_str)
+            .flat_map(str::split_ascii_whitespace)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Validates that the images do not contain tokens that user agents ignore.
+fn validate_image_tokens(
+    members: &serde_json::Map<String, serde_json::Value>,
+    report: &mut ValidationReport,
+) {
+    let mut images = Vec::new();
+
+    for member in ["icons", "screenshots"] {
+        for (index, image) in array(members.get(member)).iter().enumerate() {
+            images.push((format!("{}[{}]", member, index), image));
+        }
+    }
+
+    for member in ["shortcuts", "file_handlers"] {
+        for (index, parent) in array(members.get(member)).iter().enumerate() {
+            for (icon, image) in array(parent.get("icons")).iter().enumerate() {
+                images.push((format!("{}[{}].icons[{}]", member, index, icon), image));
+            }
+        }
+    }
+
+    for (path, image) in images {
+        for token in tokens(image.get("sizes")) {
+            if ImageSize::from_str(token).is_err() {
+                let kind = ValidationIssueKind::InvalidImageSize { size: token.to_string() };
+                report.push(Severity::Warning, format!("{}.sizes", path), kind);
+            }
+        }
+
+        for token in tokens(image.get("purpose")) {
+            if ImagePurpose::from_str(token).is_err() {
+                let kind = ValidationIssueKind::UnknownImagePurpose { purpose: token.to_string() };
+                report.push(Severity::Warning, format!("{}.purpose", path), kind);
+            }
+        }
+    }
+}
+
+impl WebAppManifest {
+    /// Validates the web app manifest.
+    ///
+    /// See the [module documentation][crate::validation] for more details.
+    ///
+    /// # Returns
+    ///
+    /// A report containing all found issues.
+    ///
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_with_options(&ValidationOptions::default())
+    }
+
+    /// Validates the web app manifest with the provided options.
+    ///
+    /// See the [module documentation][crate::validation] for more details.
+    ///
+    /// # Returns
+    ///
+    /// A report containing all found issues.
+    ///
+    pub fn validate_with_options(&self, options: &ValidationOptions) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        validate_security(self, options, &mut report);
+        validate_hosts(self, &mut report);
+        validate_names(self, options, &mut report);
+        validate_file_handlers(self, &mut report);
+        validate_keywords(self, &mut report);
+        validate_display_override(self, &mut report);
+
+        report
+    }
+}
+
+/// Checks whether the URL host is `localhost` or a loopback address.
+fn is_loopback(url: &AbsoluteUrl) -> bool {
+    match url.host() {
+        Some(Host::Domain(domain)) => domain == "localhost" || domain.ends_with(".localhost"),
+        Some(Host::Ipv4(address)) => address.is_loopback(),
+        Some(Host::Ipv6(address)) => address.is_loopback(),
+        None => false,
+    }
+}
+
+/// Validates that the application URLs are served over a secure connection.
+fn validate_security(
+    manifest: &WebAppManifest,
+    options: &ValidationOptions,
+    report: &mut ValidationReport,
+) {
+    let urls = vec![
+        ("start_url".to_string(), &manifest.start_url),
+        ("scope".to_string(), &manifest.scope),
+    ];
+
+    let icons = manifest
+        .icons
+        .iter()
+        .enumerate()
+        .map(|(index, icon)| (format!("icons[{}].src", index), &icon.src));
+
+    for (path, url) in urls.into_iter().chain(icons) {
+        let Url::Absolute(url) = url else { continue };
+
+        if url.scheme() != "http" {
+            continue;
+        }
+
+        let severity = match options.profile {
+            ValidationProfile::Production => Severity::Error,
+            ValidationProfile::Development if is_loopback(url) => continue,
+            ValidationProfile::Development => Severity::Warning,
+        };
+
+        report.push(severity, path, ValidationIssueKind::InsecureUrl { url: url.clone() });
+    }
+}
+
+/// Validates that the application hosts are not confusable.
+fn validate_hosts(manifest: &WebAppManifest, report: &mut ValidationReport) {
+    for (path, url) in [("start_url", &manifest.start_url), ("scope", &manifest.scope)] {
+        if let Some(host) = url.unicode_host() {
+            if is_confusable_host(&host) {
+                report.push(
+                    Severity::Warning,
+                    path.to_string(),
+                    ValidationIssueKind::ConfusableHost { host },
+                );
+            }
+        }
+    }
+}
+
+/// Validates that the application names do not spoof other names.
+fn validate_names(
+    manifest: &WebAppManifest,
+    options: &ValidationOptions,
+    report: &mut ValidationReport,
+) {
+    let names = [("name", &manifest.name), ("short_name", &manifest.short_name)];
+
+    for (path, name) in names.iter().filter_map(|(path, name)| Some((*path, name.as_ref()?))) {
+        if is_padded_name(name) {
+            let kind = ValidationIssueKind::PaddedName { name: name.clone() };
+            report.push(Severity::Warning, path.to_string(), kind);
+        }
+
+        if name.split_whitespace().any(is_confusable_word) {
+            let kind = ValidationIssueKind::ConfusableName { name: name.clone() };
+            report.push(Severity::Warning, path.to_string(), kind);
+        }
+
+        let skeleton = name_skeleton(name);
+        for protected in &options.protected_names {
+            let protected_skeleton = name_skeleton(protected);
+
+            if !protected_skeleton.is_empty()
+                && name != protected
+                && skeleton.contains(&protected_skeleton)
+            {
+                let kind = ValidationIssueKind::ImitatedName {
+                    name: name.clone(),
+                    protected: protected.clone(),
+                };
+                report.push(Severity::Warning, path.to_string(), kind);
+            }
+        }
+    }
+}
+
+/// Checks whether the name has surrounding or repeated whitespace or invisible characters.
+fn is_padded_name(name: &str) -> bool {
+    let invisible = |char: char| {
+        char.is_control()
+            || matches!(
+                char,
+                '\u{200b}' | '\u{2060}' | '\u{feff}' | '\u{180e}' | '\u{3164}' | '\u{115f}'
+            )
+    };
+
+    name.trim() != name || name.contains("  ") || name.chars().any(invisible)
+}
+
+/// Reduces the name to a skeleton of lower-cased Latin letters and digits for comparison.
+fn name_skeleton(name: &str) -> String {
+    const LATIN: &str = "acehijlopqswxydoaiuvp";
+
+    fold_search_text(name)
+        .chars()
+        .map(|char| match LOOKALIKES.chars().position(|lookalike| lookalike == char) {
+            Some(index) => LATIN.chars().nth(index).unwrap_or(char),
+            None => char,
+        })
+        .map(|char| match char {
+            '0' => 'o',
+            '1' => 'l',
+            char => char,
+        })
+        .filter(|char| char.is_alphanumeric())
+        .collect()
+}
+
+/// Validates accept maps and action URLs of file handlers.
+fn validate_file_handlers(manifest: &WebAppManifest, report: &mut ValidationReport) {
+    for (index, file_handler) in manifest.file_handlers.iter().enumerate() {
+        let path = format!("file_handlers[{}]", index);
+
+        if file_handler.accept.is_empty() {
+            report.push(
+                Severity::Error,
+                format!("{}.accept", path),
+                ValidationIssueKind::EmptyAccept,
+            );
+        }
+
+        for (media_type, extensions) in &file_handler.accept {
+            if MediaRange::from_str(media_type).is_err() {
+                report.push(
+                    Severity::Error,
+                    format!("{}.accept", path),
+                    ValidationIssueKind::InvalidMediaType { media_type: media_type.clone() },
+                );
+            }
+
+            for extension in extensions {
+                if !extension.starts_with('.') {
+                    report.push(
+                        Severity::Error,
+                        format!("{}.accept[{:?}]", path, media_type),
+                        ValidationIssueKind::InvalidFileExtension { extension: extension.clone() },
+                    );
+                }
+            }
+        }
+
+        if let (Url::Absolute(action), Url::Absolute(scope)) =
+            (&file_handler.action, &manifest.scope)
+        {
+            if !is_within_scope(action, scope) {
+                report.push(
+                    Severity::Error,
+                    format!("{}.action", path),
+                    ValidationIssueKind::NotWithinScope {
+                        url: action.clone(),
+                        scope: scope.clone(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Validates that the keyword members do not contain unknown values, which user agents ignore.
+fn validate_keywords(manifest: &WebAppManifest, report: &mut ValidationReport) {
+    if let Direction::Unknown(value) = &manifest.dir {
+        let kind = ValidationIssueKind::UnknownKeyword { value: value.clone() };
+        report.push(Severity::Warning, "dir".to_string(), kind);
+    }
+
+    if let Display::Unknown(mode) = &manifest.display {
+        let kind = ValidationIssueKind::UnknownDisplayMode { mode: mode.clone() };
+        report.push(Severity::Warning, "display".to_string(), kind);
+    }
+
+    if let Orientation::Unknown(value) = &manifest.orientation {
+        let kind = ValidationIssueKind::UnknownKeyword { value: value.clone() };
+        report.push(Severity::Warning, "orientation".to_string(), kind);
+    }
+
+    for (index, screenshot) in manifest.screenshots.iter().enumerate() {
+        if let Some(FormFactor::Unknown(value)) = &screenshot.form_factor {
+            let kind = ValidationIssueKind::UnknownKeyword { value: value.clone() };
+            report.push(Severity::Warning, format!("screenshots[{}].form_factor", index), kind);
+        }
+    }
+}
+
+/// Validates that the display override sequence does not contain unknown, duplicate or unreachable display modes.
+///
+/// The effective order can be obtained with [`display_resolution_order`][WebAppManifest::display_resolution_order].
+fn validate_display_override(manifest: &WebAppManifest, report: &mut ValidationReport) {
+    let mut seen = Vec::new();
+    let mut terminated = false;
+
+    for (index, mode) in manifest.display_override.iter().enumerate() {
+        let path = format!("display_override[{}]", index);
+        let name = mode.to_string();
+
+        if terminated {
+            report.push(
+                Severity::Warning,
+                path,
+                ValidationIssueKind::UnreachableDisplayMode { mode: name },
+            );
+        } else if seen.contains(&mode) {
+            report.push(
+                Severity::Warning,
+                path,
+                ValidationIssueKind::DuplicateDisplayMode { mode: name },
+            );
+        } else if let DisplayOverride::Unknown(_) = mode {
+            report.push(
+                Severity::Warning,
+                path,
+                ValidationIssueKind::UnknownDisplayMode { mode: name },
+            );
+        }
+
+        terminated |= *mode == DisplayOverride::Browser;
+        seen.push(mode);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use assert_matches::assert_matches;
+    use parameterized::parameterized;
+
+    use super::*;
+    use crate::resources::*;
+
+    #[test]
+    fn test_valid_file_handlers() {
+        let manifest = WebAppManifest {
+            scope: Url::Absolute(AbsoluteUrl::parse("https://example.com/app/").unwrap()),
+            file_handlers: vec![FileHandlerResource {
+                action: Url::Absolute(AbsoluteUrl::parse("https://example.com/app/open").unwrap()),
+                accept: BTreeMap::from([("text/csv".to_string(), vec![".csv".to_string()])]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let report = manifest.validate();
+        assert!(report.is_valid());
+        assert_eq!(report.issues.len(), 0);
+    }
+
+    #[test]
+    fn test_invalid_file_handlers() {
+        let manifest = WebAppManifest {
+            scope: Url::Absolute(AbsoluteUrl::parse("https://example.com/app/").unwrap()),
+            file_handlers: vec![
+                FileHandlerResource {
+                    action: Url::Absolute(AbsoluteUrl::parse("https://example.com/open").unwrap()),
+                    accept: BTreeMap::from([
+                        ("text".to_string(), vec![".txt".to_string()]),
+                        ("text/csv".to_string(), vec!["csv".to_string()]),
+                    ]),
+                    ..Default::default()
+                },
+                FileHandlerResource {
+                    action: Url::Absolute(
+                        AbsoluteUrl::parse("https://example.com/app/open").unwrap(),
+                    ),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let report = manifest.validate();
+        assert!(!report.is_valid());
+        assert_eq!(report.issues.len(), 4);
+
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::InvalidMediaType { media_type } if media_type == "text");
+        assert_matches!(&report.issues[1].kind, ValidationIssueKind::InvalidFileExtension { extension } if extension == "csv");
+        assert_matches!(&report.issues[2].kind, ValidationIssueKind::NotWithinScope { url: _, scope: _ });
+        assert_matches!(&report.issues[3].kind, ValidationIssueKind::EmptyAccept);
+
+        assert_eq!(report.issues[1].path, r#"file_handlers[0].accept["text/csv"]"#);
+        assert_eq!(report.issues[3].path, "file_handlers[1].accept");
+    }
+
+    #[test]
+    fn test_confusable_hosts() {
+        let manifest = WebAppManifest {
+            start_url: Url::Absolute(AbsoluteUrl::parse("https://xn--pypal-4ve.com/").unwrap()),
+            scope: Url::Absolute(AbsoluteUrl::parse("https://xn--pypal-4ve.com/").unwrap()),
+            ..Default::default()
+        };
+
+        let report = manifest.validate();
+        assert!(report.is_valid());
+        assert_eq!(report.warnings().count(), 2);
+
+        assert_eq!(report.issues[0].path, "start_url");
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::ConfusableHost { host } if host == "pаypal.com");
+    }
+
+    #[test]
+    fn test_spoofed_names() {
+        let manifest = WebAppManifest {
+            name: Some(" Pаypal\u{200b} ".to_string()),
+            short_name: Some("PayPal".to_string()),
+            ..Default::default()
+        };
+
+        let report = manifest.validate();
+        assert!(report.is_valid());
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::PaddedName { .. });
+        assert_matches!(&report.issues[1].kind, ValidationIssueKind::ConfusableName { .. });
+        assert_eq!(report.issues.len(), 2);
+
+        let options =
+            ValidationOptions { protected_names: vec!["PayPal".to_string()], ..Default::default() };
+        let report = manifest.validate_with_options(&options);
+        assert_eq!(report.issues.len(), 3);
+        assert_matches!(&report.issues[2].kind, ValidationIssueKind::ImitatedName { protected, .. } if protected == "PayPal");
+
+        let manifest =
+            WebAppManifest { name: Some("Pay-Pa1 Wallet".to_string()), ..Default::default() };
+        let report = manifest.validate_with_options(&options);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].path, "name");
+
+        let manifest =
+            WebAppManifest { name: Some("Παράδειγμα".to_string()), ..Default::default() };
+        assert_eq!(manifest.validate_with_options(&options).issues, []);
+    }
+
+    #[test]
+    fn test_insecure_urls() {
+        let manifest = WebAppManifest {
+            start_url: Url::Absolute(AbsoluteUrl::parse("http://example.com/").unwrap()),
+            scope: Url::Absolute(AbsoluteUrl::parse("http://example.com/").unwrap()),
+            icons: vec![IconResource {
+                src: Url::Absolute(AbsoluteUrl::parse("https://example.com/icon.png").unwrap()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let report = manifest.validate();
+        assert_eq!(report.errors().count(), 2);
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::InsecureUrl { url: _ });
+
+        let options =
+            ValidationOptions { profile: ValidationProfile::Development, ..Default::default() };
+        let report = manifest.validate_with_options(&options);
+        assert!(report.is_valid());
+        assert_eq!(report.warnings().count(), 2);
+    }
+
+    #[parameterized(url = {
+        "http://localhost:8080/",
+        "http://app.localhost/",
+        "http://127.0.0.1/",
+        "http://[::1]:3000/",
+    })]
+    fn test_development_loopback_urls(url: &str) {
+        let manifest = WebAppManifest {
+            start_url: Url::Absolute(AbsoluteUrl::parse(url).unwrap()),
+            scope: Url::Absolute(AbsoluteUrl::parse(url).unwrap()),
+            ..Default::default()
+        };
+
+        assert_eq!(manifest.validate().errors().count(), 2);
+
+        let options =
+            ValidationOptions { profile: ValidationProfile::Development, ..Default::default() };
+        assert_eq!(manifest.validate_with_options(&options).issues.len(), 0);
+    }
+
+    #[test]
+    fn test_validate_unknown_keywords() {
+        let manifest: WebAppManifest = serde_json::from_str(
+            r#"{
+            "dir": "ttb",
+            "display": "picture-in-picture",
+            "orientation": "portrait",
+            "screenshots": [{ "src": "screenshot.png", "form_factor": "foldable" }]
+        }"#,
+        )
+        .unwrap();
+
+        let report = manifest.validate();
+        assert!(report.is_valid());
+        assert_eq!(report.issues.len(), 3);
+
+        assert_eq!(report.issues[0].path, "dir");
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::UnknownKeyword { value } if value == "ttb");
+
+        assert_eq!(report.issues[1].path, "display");
+        assert_matches!(&report.issues[1].kind, ValidationIssueKind::UnknownDisplayMode { mode } if mode == "picture-in-picture");
+
+        assert_eq!(report.issues[2].path, "screenshots[0].form_factor");
+        assert_matches!(&report.issues[2].kind, ValidationIssueKind::UnknownKeyword { value } if value == "foldable");
+    }
+
+    #[test]
+    fn test_validate_display_override() {
+        let manifest = WebAppManifest {
+            display_override: vec![
+                DisplayOverride::Tabbed,
+                DisplayOverride::Unknown("future".to_string()),
+                DisplayOverride::Tabbed,
+                DisplayOverride::Browser,
+                DisplayOverride::Standalone,
+            ],
+            ..Default::default()
+        };
+
+        let report = manifest.validate();
+        assert!(report.is_valid());
+        assert_eq!(report.issues.len(), 3);
+
+        assert_eq!(report.issues[0].path, "display_override[1]");
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::UnknownDisplayMode { mode } if mode == "future");
+
+        assert_eq!(report.issues[1].path, "display_override[2]");
+        assert_matches!(&report.issues[1].kind, ValidationIssueKind::DuplicateDisplayMode { mode } if mode == "tabbed");
+
+        assert_eq!(report.issues[2].path, "display_override[4]");
+        assert_matches!(&report.issues[2].kind, ValidationIssueKind::UnreachableDisplayMode { mode } if mode == "standalone");
+    }
+
+    #[test]
+    fn test_validate_unknown_image_purposes() {
+        let document = serde_json::json!({
+            "icons": [{ "src": "icon.png", "purpose": "any fancy" }],
+            "shortcuts": [{ "name": "A", "url": "/a", "icons": [{ "src": "a.png", "purpose": "glossy" }] }],
+        });
+
+        let report = validate_members(&document);
+        assert!(report.is_valid());
+        assert_eq!(report.issues.len(), 2);
+
+        assert_eq!(report.issues[0].path, "icons[0].purpose");
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::UnknownImagePurpose { purpose } if purpose == "fancy");
+
+        assert_eq!(report.issues[1].path, "shortcuts[0].icons[0].purpose");
+    }
+
+    #[test]
+    fn test_validate_invalid_image_sizes() {
+        let document = serde_json::json!({
+            "icons": [{ "src": "icon.png", "sizes": "48x48 96" }],
+            "screenshots": [{ "src": "screenshot.png", "sizes": ["1280x720", "wide"] }],
+        });
+
+        let report = validate_members(&document);
+        assert!(report.is_valid());
+        assert_eq!(report.issues.len(), 2);
+
+        assert_eq!(report.issues[0].path, "icons[0].sizes");
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::InvalidImageSize { size } if size == "96");
+
+        assert_eq!(report.issues[1].path, "screenshots[0].sizes");
+        assert_matches!(&report.issues[1].kind, ValidationIssueKind::InvalidImageSize { size } if size == "wide");
+    }
+
+    #[test]
+    fn test_validate_deprecated_members() {
+        let document = serde_json::json!({
+            "name": "Example",
+            "serviceworker": { "src": "sw.js" },
+            "capture_links": "existing-client-navigate",
+        });
+
+        let report = validate_members(&document);
+        assert!(report.is_valid());
+        assert_eq!(report.issues.len(), 2);
+
+        assert_eq!(report.issues[0].path, "serviceworker");
+        assert_matches!(&report.issues[0].kind, ValidationIssueKind::DeprecatedMember { replacement: None, .. });
+
+        assert_eq!(report.issues[1].path, "capture_links");
+        assert_eq!(report.issues[1].kind.to_string(), "Provided member (capture_links) is deprecated, use `launch_handler` instead");
+
+        assert!(validate_members(&serde_json::json!([])).issues.is_empty());
+    }
+}
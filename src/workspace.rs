@@ -0,0 +1,217 @@
+//! Contains analysis of multiple manifests hosted together.
+//!
+//! Organizations that host many web applications need to ensure they do not interfere
+//! with each other when installed on the same device. [`ManifestWorkspace`] holds a set
+//! of processed manifests and [detects conflicts][WorkspaceConflict] between them, such
+//! as overlapping scopes or protocol handlers registered by multiple applications.
+
+#![allow(missing_docs)]
+
+use std::collections::BTreeMap;
+
+use crate::types::*;
+use crate::{is_within_scope, WebAppManifest};
+
+/// A conflict between manifests of the workspace.
+///
+/// Manifests are identified by the keys they were added with.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum WorkspaceConflict {
+    /// The scope of the inner manifest is within the scope of the outer manifest, so
+    /// navigations to the inner application may be captured by the outer one.
+    OverlappingScopes { outer: String, inner: String },
+
+    /// Multiple manifests have the same identity (their processed `id`), so user agents
+    /// treat them as the same application.
+    DuplicateId { id: String, manifests: Vec<String> },
+
+    /// Multiple manifests handle the same protocol scheme, so users need to choose
+    /// which application should handle it.
+    SharedProtocol { protocol: String, manifests: Vec<String> },
+}
+
+/// A report of all conflicts found in the workspace.
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+pub struct WorkspaceReport {
+    /// The found conflicts, grouped by their kind.
+    pub conflicts: Vec<WorkspaceConflict>,
+}
+
+impl WorkspaceReport {
+    /// Returns `true` if there are no conflicts.
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// A set of processed manifests that are analyzed together.
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct ManifestWorkspace {
+    manifests: Vec<(String, WebAppManifest)>,
+}
+
+impl ManifestWorkspace {
+    /// Creates an empty workspace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the processed manifest with a key that identifies it in reports, such as
+    /// its file path or the name of its project.
+    pub fn insert(&mut self, key: impl Into<String>, manifest: WebAppManifest) {
+        self.manifests.push((key.into(), manifest));
+    }
+
+    /// Returns the number of manifests in the workspace.
+    pub fn len(&self) -> usize {
+        self.manifests.len()
+    }
+
+    /// Returns `true` if the workspace does not contain any manifests.
+    pub fn is_empty(&self) -> bool {
+        self.manifests.is_empty()
+    }
+
+    /// Analyzes the manifests and reports conflicts between them.
+    ///
+    /// Manifests should be processed first, as rules that require absolute URLs
+    /// skip unprocessed manifests.
+    ///
+    /// # Returns
+    ///
+    /// A report containing all found conflicts.
+    ///
+    pub fn analyze(&self) -> WorkspaceReport {
+        let mut report = WorkspaceReport::default();
+
+        self.analyze_scopes(&mut report);
+        self.analyze_ids(&mut report);
+        self.analyze_protocols(&mut report);
+
+        report
+    }
+
+    fn analyze_scopes(&self, report: &mut WorkspaceReport) {
+        for (index, (outer_key, outer)) in self.manifests.iter().enumerate() {
+            let Url::Absolute(outer_scope) = &outer.scope else { continue };
+
+            for (other_index, (inner_key, inner)) in self.manifests.iter().enumerate() {
+                let Url::Absolute(inner_scope) = &inner.scope else { continue };
+
+                // Equal scopes are only reported once, for the manifest that was added first
+                let reported =
+                    index == other_index || (inner_scope == outer_scope && other_index < index);
+
+                if !reported && is_within_scope(inner_scope, outer_scope) {
+                    report.conflicts.push(WorkspaceConflict::OverlappingScopes {
+                        outer: outer_key.clone(),
+                        inner: inner_key.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn analyze_ids(&self, report: &mut WorkspaceReport) {
+        let mut ids: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+
+        for (key, manifest) in &self.manifests {
+            if let Some(Url::Absolute(id)) = &manifest.id {
+                ids.entry(id.as_str()).or_default().push(key.clone());
+            }
+        }
+
+        for (id, manifests) in ids.into_iter().filter(|(_, manifests)| manifests.len() > 1) {
+            report.conflicts.push(WorkspaceConflict::DuplicateId { id: id.to_string(), manifests });
+        }
+    }
+
+    fn analyze_protocols(&self, report: &mut WorkspaceReport) {
+        let mut protocols: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for (key, manifest) in &self.manifests {
+            for protocol_handler in &manifest.protocol_handlers {
+                let handlers =
+                    protocols.entry(protocol_handler.protocol.to_ascii_lowercase()).or_default();
+
+                if !handlers.contains(key) {
+                    handlers.push(key.clone());
+                }
+            }
+        }
+
+        for (protocol, manifests) in
+            protocols.into_iter().filter(|(_, manifests)| manifests.len() > 1)
+        {
+            report.conflicts.push(WorkspaceConflict::SharedProtocol { protocol, manifests });
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::needless_update)]
+#[rustfmt::skip::macros(assert_eq, assert_matches, assert)]
+mod tests {
+    use super::*;
+    use crate::resources::*;
+
+    fn manifest(start_url: &str, scope: &str, protocols: &[&str]) -> WebAppManifest {
+        let base = AbsoluteUrl::parse("https://example.com").unwrap();
+
+        WebAppManifest {
+            id: Some(Url::Absolute(base.join(start_url).unwrap())),
+            start_url: Url::Absolute(base.join(start_url).unwrap()),
+            scope: Url::Absolute(base.join(scope).unwrap()),
+            protocol_handlers: protocols
+                .iter()
+                .map(|protocol| ProtocolHandlerResource {
+                    protocol: protocol.to_string(),
+                    url: Url::Absolute(base.join(scope).unwrap()),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_analyze_workspace() {
+        let mut workspace = ManifestWorkspace::new();
+        workspace.insert("portal", manifest("/", "/", &["web+mail"]));
+        workspace.insert("mail", manifest("/mail/", "/mail/", &["web+mail", "mailto"]));
+        workspace.insert("mail-beta", manifest("/mail/", "/mail/", &[]));
+        workspace.insert("docs", manifest("/docs/", "/docs/", &["MAILTO"]));
+
+        assert_eq!(workspace.len(), 4);
+
+        let report = workspace.analyze();
+        assert_eq!(report.conflicts, [
+            WorkspaceConflict::OverlappingScopes { outer: "portal".to_string(), inner: "mail".to_string() },
+            WorkspaceConflict::OverlappingScopes { outer: "portal".to_string(), inner: "mail-beta".to_string() },
+            WorkspaceConflict::OverlappingScopes { outer: "portal".to_string(), inner: "docs".to_string() },
+            WorkspaceConflict::OverlappingScopes { outer: "mail".to_string(), inner: "mail-beta".to_string() },
+            WorkspaceConflict::DuplicateId {
+                id: "https://example.com/mail/".to_string(),
+                manifests: vec!["mail".to_string(), "mail-beta".to_string()],
+            },
+            WorkspaceConflict::SharedProtocol {
+                protocol: "mailto".to_string(),
+                manifests: vec!["mail".to_string(), "docs".to_string()],
+            },
+            WorkspaceConflict::SharedProtocol {
+                protocol: "web+mail".to_string(),
+                manifests: vec!["portal".to_string(), "mail".to_string()],
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_analyze_empty_workspace() {
+        let mut workspace = ManifestWorkspace::new();
+        assert!(workspace.is_empty());
+        assert!(workspace.analyze().is_empty());
+
+        workspace.insert("app", manifest("/app/", "/app/", &["web+app"]));
+        workspace.insert("other", manifest("/other/", "/other/", &[]));
+        assert!(workspace.analyze().is_empty());
+    }
+}